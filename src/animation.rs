@@ -0,0 +1,82 @@
+use crate::vec::{Quat, Vec3};
+
+/// Values that can be blended between keyframes
+pub trait Interpolate {
+    /// Blend from `self` at `t = 0.0` to `other` at `t = 1.0`
+    fn interpolate(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + t * (other - self)
+    }
+}
+
+impl Interpolate for Vec3 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        *self + t * (*other - *self)
+    }
+}
+
+impl Interpolate for Quat {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.slerp(*other, t)
+    }
+}
+
+/// Value that changes over time, interpolated between keys. Before the first
+/// key and after the last, the value holds steady.
+#[derive(Debug, Clone)]
+pub struct Keyframes<T> {
+    /// `(time, value)` pairs sorted by time
+    keys: Vec<(f64, T)>,
+}
+
+impl<T> Keyframes<T>
+where
+    T: Interpolate + Clone,
+{
+    /// Value that never changes
+    pub fn constant(value: T) -> Self {
+        Self::new(vec![(0.0, value)])
+    }
+
+    /// Panics if there are no keys
+    pub fn new(mut keys: Vec<(f64, T)>) -> Self {
+        assert!(!keys.is_empty(), "keyframes need at least one key");
+        keys.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Self { keys }
+    }
+
+    /// Add a key, replacing the value of any existing key at the same time
+    pub fn with_key(mut self, time: f64, value: T) -> Self {
+        match self.keys.binary_search_by(|(t, _)| t.total_cmp(&time)) {
+            Ok(i) => self.keys[i].1 = value,
+            Err(i) => self.keys.insert(i, (time, value)),
+        }
+        self
+    }
+
+    /// Times of the keys, in order
+    pub fn times(&self) -> impl Iterator<Item = f64> + '_ {
+        self.keys.iter().map(|(time, _)| *time)
+    }
+
+    /// Values at the keys, in time order
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.keys.iter().map(|(_, value)| value)
+    }
+
+    pub fn at(&self, time: f64) -> T {
+        // Index of the first key after `time`
+        let next = self.keys.partition_point(|(t, _)| *t <= time);
+        match (next.checked_sub(1), self.keys.get(next)) {
+            (Some(previous), Some((t1, v1))) => {
+                let (t0, v0) = &self.keys[previous];
+                v0.interpolate(v1, (time - t0) / (t1 - t0))
+            }
+            (Some(previous), None) => self.keys[previous].1.clone(),
+            (None, _) => self.keys[0].1.clone(),
+        }
+    }
+}
@@ -1,68 +1,64 @@
-use rand::distributions::Uniform;
+use rand::distributions::{Distribution, Uniform};
 use rand::rngs::ThreadRng;
 
 use crate::ray::Ray;
 use crate::vec::{Point, Vec3};
 
-pub struct Camera {
-    origin: Point,
-    horizontal: Vec3,
-    vertical: Vec3,
-    lower_left_corner: Point,
-    u: Vec3,
-    v: Vec3,
-    // w: Vec3,
-    lens_radius: f64,
-}
-
-impl Camera {
-    pub fn new(
-        look_from: Point,
-        look_at: Point,
-        up_vector: Vec3,
-        vertical_field_of_view: f64,
-        aspect_ratio: f64,
-        aperture: f64,
-        focus_distance: f64,
-    ) -> Self {
-        let theta = vertical_field_of_view.to_radians();
-        let h = (theta / 2.0).tan();
-        let viewport_height = 2.0 * h;
-        let viewport_width = viewport_height * aspect_ratio;
+pub mod equirectangular;
+pub mod fisheye;
+pub mod orthographic;
+pub mod perspective;
+pub mod stereo;
 
-        // Unit vector pointing toward the camera on the boresight
-        let w = (look_from - look_at).unit();
-        // Unit vector to the right in the frame
-        let u = up_vector.cross(w).unit();
-        // Unit vector pointing up in the frame
-        let v = w.cross(u);
+pub use equirectangular::Equirectangular;
+pub use fisheye::{Fisheye, FisheyeProjection};
+pub use orthographic::Orthographic;
+pub use perspective::{Aperture, Mask, Perspective};
+pub use stereo::{Stereo, StereoLayout};
 
-        let origin = look_from;
-        let horizontal = focus_distance * viewport_width * u;
-        let vertical = focus_distance * viewport_height * v;
-        let lower_left_corner = origin - horizontal / 2.0 - vertical / 2.0 - focus_distance * w;
+/// Maps points on the image to rays into the scene
+pub trait Camera: Send + Sync {
+    /// Ray for the image point `(s, t)`, each in 0.0..=1.0, with `(0, 0)` at
+    /// the bottom left
+    fn get_ray(&self, s: f64, t: f64, vec_dist: &Uniform<f64>, rng: &mut ThreadRng) -> Ray;
 
-        let lens_radius = aperture / 2.0;
-
-        Self {
-            origin,
-            horizontal,
-            vertical,
-            lower_left_corner,
-            u,
-            v,
-            // w,
-            lens_radius,
+    /// Keep the shutter open from time `open` to `close`, blurring anything
+    /// that moves in between. Otherwise every ray is at time 0.0.
+    fn with_shutter(self, open: f64, close: f64) -> Shutter<Self>
+    where
+        Self: Sized,
+    {
+        Shutter {
+            camera: self,
+            open,
+            close,
         }
     }
+}
 
-    pub fn get_ray(&self, s: f64, t: f64, vec_dist: &Uniform<f64>, rng: &mut ThreadRng) -> Ray {
-        let random_disc = self.lens_radius * Vec3::random_inside_unit_disc(vec_dist, rng);
-        let offset = self.u * random_disc.x + self.v * random_disc.y;
+/// Unit vectors `(u, v, w)` to the right, up, and back toward the camera
+/// along the boresight
+fn basis(look_from: Point, look_at: Point, up_vector: Vec3) -> (Vec3, Vec3, Vec3) {
+    let w = (look_from - look_at).unit();
+    let u = up_vector.cross(w).unit();
+    let v = w.cross(u);
+    (u, v, w)
+}
+
+/// Camera whose rays are spread over the time its shutter is open
+#[derive(Debug, Clone, Copy)]
+pub struct Shutter<C> {
+    camera: C,
+    open: f64,
+    close: f64,
+}
 
-        Ray::new(
-            self.origin + offset,
-            self.lower_left_corner + s * self.horizontal + t * self.vertical - self.origin - offset,
-        )
+impl<C: Camera> Camera for Shutter<C> {
+    fn get_ray(&self, s: f64, t: f64, vec_dist: &Uniform<f64>, rng: &mut ThreadRng) -> Ray {
+        let fraction = (vec_dist.sample(rng) + 1.0) / 2.0;
+        Ray {
+            time: self.open + fraction * (self.close - self.open),
+            ..self.camera.get_ray(s, t, vec_dist, rng)
+        }
     }
 }
@@ -0,0 +1,42 @@
+use rand::distributions::Uniform;
+use rand::rngs::ThreadRng;
+
+use super::{basis, Camera};
+use crate::ray::Ray;
+use crate::vec::{Point, Vec3};
+
+use std::f64::consts::PI;
+
+/// Full 360° panorama in the
+/// [equirectangular projection](https://en.wikipedia.org/wiki/Equirectangular_projection)
+/// used by VR viewers. Longitude runs across the image and latitude up it, so
+/// images should be twice as wide as they are tall.
+pub struct Equirectangular {
+    origin: Point,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+}
+
+impl Equirectangular {
+    /// Panorama from `look_from`, with `look_at` in the center of the image
+    pub fn new(look_from: Point, look_at: Point, up_vector: Vec3) -> Self {
+        let (u, v, w) = basis(look_from, look_at, up_vector);
+        Self {
+            origin: look_from,
+            u,
+            v,
+            w,
+        }
+    }
+}
+
+impl Camera for Equirectangular {
+    fn get_ray(&self, s: f64, t: f64, _vec_dist: &Uniform<f64>, _rng: &mut ThreadRng) -> Ray {
+        let longitude = (s - 0.5) * 2.0 * PI;
+        let latitude = (t - 0.5) * PI;
+        let direction = latitude.cos() * (longitude.sin() * self.u - longitude.cos() * self.w)
+            + latitude.sin() * self.v;
+        Ray::new(self.origin, direction)
+    }
+}
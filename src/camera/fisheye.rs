@@ -0,0 +1,83 @@
+use rand::distributions::Uniform;
+use rand::rngs::ThreadRng;
+
+use super::{basis, Camera};
+use crate::ray::Ray;
+use crate::vec::{Point, Vec3};
+
+use std::f64::consts::PI;
+
+/// How a [fisheye lens](https://en.wikipedia.org/wiki/Fisheye_lens#Mapping_function)
+/// maps the angle from the boresight to distance from the image center
+#[derive(Debug, Clone, Copy)]
+pub enum FisheyeProjection {
+    /// Distance proportional to angle, keeping angular spacing even
+    Equidistant,
+    /// Distance proportional to the sine of half the angle, keeping areas in
+    /// proportion to solid angle
+    Equisolid,
+}
+
+/// Ultra-wide lens whose image circle spans the full height of the frame.
+/// Corners beyond the circle see past the field of view, as with a full-frame
+/// fisheye.
+pub struct Fisheye {
+    origin: Point,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+    /// Angle from the boresight at the edge of the image circle, in radians
+    half_field_of_view: f64,
+    aspect_ratio: f64,
+    projection: FisheyeProjection,
+}
+
+impl Fisheye {
+    /// Fisheye seeing `field_of_view` degrees across the image circle, e.g.
+    /// 180.0 for a hemisphere
+    pub fn new(
+        look_from: Point,
+        look_at: Point,
+        up_vector: Vec3,
+        field_of_view: f64,
+        aspect_ratio: f64,
+        projection: FisheyeProjection,
+    ) -> Self {
+        let (u, v, w) = basis(look_from, look_at, up_vector);
+        Self {
+            origin: look_from,
+            u,
+            v,
+            w,
+            half_field_of_view: field_of_view.to_radians() / 2.0,
+            aspect_ratio,
+            projection,
+        }
+    }
+}
+
+impl Camera for Fisheye {
+    fn get_ray(&self, s: f64, t: f64, _vec_dist: &Uniform<f64>, _rng: &mut ThreadRng) -> Ray {
+        // Position relative to the image circle, which has radius 1
+        let x = (2.0 * s - 1.0) * self.aspect_ratio;
+        let y = 2.0 * t - 1.0;
+        let radius = (x.powi(2) + y.powi(2)).sqrt();
+
+        let theta = match self.projection {
+            FisheyeProjection::Equidistant => radius * self.half_field_of_view,
+            FisheyeProjection::Equisolid => {
+                let sine = (radius * (self.half_field_of_view / 2.0).sin()).min(1.0);
+                2.0 * sine.asin()
+            }
+        }
+        .min(PI);
+        let around = if radius > 0.0 {
+            (x * self.u + y * self.v) / radius
+        } else {
+            Vec3::ZERO
+        };
+        let direction = theta.sin() * around - theta.cos() * self.w;
+
+        Ray::new(self.origin, direction)
+    }
+}
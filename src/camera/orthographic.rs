@@ -0,0 +1,44 @@
+use rand::distributions::Uniform;
+use rand::rngs::ThreadRng;
+
+use super::{basis, Camera};
+use crate::ray::Ray;
+use crate::vec::{Point, Vec3};
+
+/// Parallel projection without perspective, for technical views where sizes
+/// must not change with distance
+pub struct Orthographic {
+    /// Center of the view
+    origin: Point,
+    /// Full width and height of the view along the right and up directions
+    horizontal: Vec3,
+    vertical: Vec3,
+    direction: Vec3,
+}
+
+impl Orthographic {
+    /// View `view_height` units tall, looking from `look_from` toward
+    /// `look_at`. Objects behind `look_from` are not seen.
+    pub fn new(
+        look_from: Point,
+        look_at: Point,
+        up_vector: Vec3,
+        view_height: f64,
+        aspect_ratio: f64,
+    ) -> Self {
+        let (u, v, w) = basis(look_from, look_at, up_vector);
+        Self {
+            origin: look_from,
+            horizontal: view_height * aspect_ratio * u,
+            vertical: view_height * v,
+            direction: -w,
+        }
+    }
+}
+
+impl Camera for Orthographic {
+    fn get_ray(&self, s: f64, t: f64, _vec_dist: &Uniform<f64>, _rng: &mut ThreadRng) -> Ray {
+        let origin = self.origin + (s - 0.5) * self.horizontal + (t - 0.5) * self.vertical;
+        Ray::new(origin, self.direction)
+    }
+}
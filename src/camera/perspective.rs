@@ -0,0 +1,302 @@
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::ThreadRng;
+
+use super::{basis, Camera};
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::vec::{Mat4, Point, Vec3};
+
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+/// Shape of the lens opening, which gives out-of-focus highlights (bokeh)
+/// their shape
+#[derive(Clone)]
+pub enum Aperture {
+    /// Perfectly round opening
+    Circle,
+    /// Regular polygon formed by straight diaphragm blades, with one corner
+    /// rotated counterclockwise from the right by `rotation` degrees
+    Blades { count: usize, rotation: f64 },
+    /// Opening traced by the brightness of a texture, e.g. a star or heart
+    /// cut-out
+    Mask(Mask),
+}
+
+/// Texture over the lens, with `(u, v)` spanning the square around it,
+/// tabulated so that points on the opening can be picked in proportion to its
+/// brightness
+#[derive(Clone)]
+pub struct Mask {
+    /// Running total of the brightness of the cells across the square, row by
+    /// row from the bottom
+    cdf: Vec<f64>,
+}
+
+impl Mask {
+    /// Cells along each side of the square
+    const RESOLUTION: usize = 256;
+
+    /// Panics if the texture is black everywhere on the opening
+    pub fn new(texture: Arc<dyn Texture>) -> Self {
+        let n = Self::RESOLUTION;
+        let mut total = 0.0;
+        let cdf = (0..n * n)
+            .map(|cell| {
+                let center = Self::cell_corner(cell) + Vec3::new(1.0, 1.0, 0.0) / n as f64;
+                if center.mag_squared() <= 1.0 {
+                    total += Self::transmittance(&texture, &center);
+                }
+                total
+            })
+            .collect();
+        assert!(total > 0.0, "aperture mask has no opening");
+        Self { cdf }
+    }
+
+    /// Lower left corner of a cell in the square around the unit disc
+    fn cell_corner(cell: usize) -> Vec3 {
+        let n = Self::RESOLUTION;
+        let size = 2.0 / n as f64;
+        Vec3::new(
+            (cell % n) as f64 * size - 1.0,
+            (cell / n) as f64 * size - 1.0,
+            0.0,
+        )
+    }
+
+    fn transmittance(texture: &Arc<dyn Texture>, p: &Vec3) -> f64 {
+        let color = texture.value((p.x + 1.0) / 2.0, (p.y + 1.0) / 2.0, p);
+        ((color.x + color.y + color.z) / 3.0).max(0.0)
+    }
+
+    /// Point on the opening in the unit disc, picking a cell by its
+    /// brightness and then a uniform point inside the disc within it
+    fn sample(&self, vec_dist: &Uniform<f64>, rng: &mut ThreadRng) -> Vec3 {
+        let mut uniform = || (vec_dist.sample(rng) + 1.0) / 2.0;
+        let total = self.cdf[self.cdf.len() - 1];
+        let target = uniform() * total;
+        let cell = self
+            .cdf
+            .partition_point(|&sum| sum <= target)
+            .min(self.cdf.len() - 1);
+
+        // The cell's center is in the disc, so some of it is
+        let corner = Self::cell_corner(cell);
+        let size = 2.0 / Self::RESOLUTION as f64;
+        loop {
+            let p = corner + size * Vec3::new(uniform(), uniform(), 0.0);
+            if p.mag_squared() <= 1.0 {
+                return p;
+            }
+        }
+    }
+}
+
+impl Aperture {
+    /// Point on the opening in the unit disc around the lens center
+    fn sample(&self, vec_dist: &Uniform<f64>, rng: &mut ThreadRng) -> Vec3 {
+        match self {
+            Aperture::Circle => Vec3::random_inside_unit_disc(vec_dist, rng),
+            Aperture::Blades { count, rotation } => {
+                let mut uniform = || (vec_dist.sample(rng) + 1.0) / 2.0;
+                // Pick one of the equal triangles fanning out from the
+                // center, then a uniform point inside it
+                let count = (*count).max(3);
+                let side = ((uniform() * count as f64) as usize).min(count - 1);
+                let corner = |i: usize| {
+                    let angle = rotation.to_radians() + 2.0 * PI * i as f64 / count as f64;
+                    Vec3::new(angle.cos(), angle.sin(), 0.0)
+                };
+                let a = uniform().sqrt();
+                let b = uniform();
+                a * (1.0 - b) * corner(side) + a * b * corner(side + 1)
+            }
+            Aperture::Mask(mask) => mask.sample(vec_dist, rng),
+        }
+    }
+}
+
+/// Pinhole or thin-lens camera with a rectilinear projection
+pub struct Perspective {
+    origin: Point,
+    /// Unit vector to the right in the frame
+    u: Vec3,
+    /// Unit vector pointing up in the frame
+    v: Vec3,
+    /// Unit vector pointing toward the camera on the boresight
+    w: Vec3,
+    /// Viewport size one unit in front of the lens
+    viewport_width: f64,
+    viewport_height: f64,
+    focus_distance: f64,
+    lens_radius: f64,
+    aperture: Aperture,
+    /// Offset of the image from the boresight, in viewport widths and heights
+    shift: (f64, f64),
+    /// Normal of the plane of focus, which faces the camera unless tilted
+    focus_normal: Vec3,
+}
+
+impl Perspective {
+    /// Largest tilt or swing of the plane of focus, in degrees
+    pub const MAX_TILT: f64 = 85.0;
+
+    pub fn new(
+        look_from: Point,
+        look_at: Point,
+        up_vector: Vec3,
+        vertical_field_of_view: f64,
+        aspect_ratio: f64,
+        aperture: f64,
+        focus_distance: f64,
+    ) -> Self {
+        let theta = vertical_field_of_view.to_radians();
+        let h = (theta / 2.0).tan();
+        let viewport_height = 2.0 * h;
+        let viewport_width = viewport_height * aspect_ratio;
+
+        let (u, v, w) = basis(look_from, look_at, up_vector);
+
+        Self {
+            origin: look_from,
+            u,
+            v,
+            w,
+            viewport_width,
+            viewport_height,
+            focus_distance,
+            lens_radius: aperture / 2.0,
+            aperture: Aperture::Circle,
+            shift: (0.0, 0.0),
+            focus_normal: w,
+        }
+    }
+
+    /// Focus at the depth of a point in the scene, e.g. a subject's eye
+    pub fn with_focus_on(mut self, target: Point) -> Self {
+        self.focus_distance = (self.origin - target).dot(self.w);
+        self
+    }
+
+    /// Set the shape of the lens opening, which is round by default
+    pub fn with_aperture(mut self, aperture: Aperture) -> Self {
+        self.aperture = aperture;
+        self
+    }
+
+    /// Shift the image across the frame without turning the camera, as a
+    /// fraction of the frame's width and height, e.g. to keep verticals
+    /// parallel when looking up at a building
+    pub fn with_shift(mut self, horizontal: f64, vertical: f64) -> Self {
+        self.shift = (horizontal, vertical);
+        self
+    }
+
+    /// Tilt the plane of focus, in degrees, about the frame's horizontal axis
+    /// (tilt) and vertical axis (swing). Positive angles move it farther away
+    /// at the top and right of the frame. It still passes through the point
+    /// on the boresight at the focus distance, so a ground plane can be kept
+    /// sharp into the distance, or a scene made to look like a miniature.
+    ///
+    /// Angles are limited to within [`MAX_TILT`](Self::MAX_TILT) of facing
+    /// the camera, since at a right angle the plane would pass through it.
+    pub fn with_tilt(mut self, tilt: f64, swing: f64) -> Self {
+        let tilt = tilt.clamp(-Self::MAX_TILT, Self::MAX_TILT);
+        let swing = swing.clamp(-Self::MAX_TILT, Self::MAX_TILT);
+        let rotation = Mat4::rotate(self.u, -tilt).then(Mat4::rotate(self.v, swing));
+        self.focus_normal = rotation.transform_vector(self.w);
+        self
+    }
+}
+
+impl Camera for Perspective {
+    fn get_ray(&self, s: f64, t: f64, vec_dist: &Uniform<f64>, rng: &mut ThreadRng) -> Ray {
+        let random_disc = self.lens_radius * self.aperture.sample(vec_dist, rng);
+        let offset = self.u * random_disc.x + self.v * random_disc.y;
+
+        // Direction through the lens center, reaching one unit in front of it
+        let pinhole = (s - 0.5 + self.shift.0) * self.viewport_width * self.u
+            + (t - 0.5 + self.shift.1) * self.viewport_height * self.v
+            - self.w;
+        // Every ray from the lens converges where the central ray meets the
+        // plane of focus. A tilted plane may run parallel to the central ray,
+        // or meet it behind the camera, so those rays focus at the focus
+        // distance instead.
+        let toward_plane = (-pinhole).dot(self.focus_normal);
+        let focus_depth = if toward_plane > 1.0e-6 {
+            self.focus_distance * self.w.dot(self.focus_normal) / toward_plane
+        } else {
+            self.focus_distance
+        };
+        let focus_point = self.origin + focus_depth * pinhole;
+
+        Ray::new(self.origin + offset, focus_point - self.origin - offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec::Color;
+
+    #[test]
+    fn tilted_camera_looks_forward() {
+        let camera = Perspective::new(
+            Point::ZERO,
+            Point::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            1.5,
+            0.5,
+            4.0,
+        )
+        .with_tilt(120.0, 60.0);
+        let vec_dist = Uniform::new_inclusive(-1.0, 1.0);
+        let mut rng = rand::thread_rng();
+
+        for i in 0..=20 {
+            for j in 0..=20 {
+                let (s, t) = (i as f64 / 20.0, j as f64 / 20.0);
+                let r = camera.get_ray(s, t, &vec_dist, &mut rng);
+                let d = r.direction;
+                assert!(
+                    d.x.is_finite() && d.y.is_finite() && d.z.is_finite(),
+                    "{:?}",
+                    d.components()
+                );
+                assert!(d.z < 0.0, "{:?}", d.components());
+            }
+        }
+    }
+
+    /// Open only in a narrow band at the right of the lens
+    struct Slit;
+
+    impl Texture for Slit {
+        fn value(&self, u: f64, _v: f64, _point: &Point) -> Color {
+            if u > 0.9 {
+                Color::ONE
+            } else {
+                Color::ZERO
+            }
+        }
+    }
+
+    #[test]
+    fn mask_samples_only_its_opening() {
+        let aperture = Aperture::Mask(Mask::new(Arc::new(Slit)));
+        let vec_dist = Uniform::new_inclusive(-1.0, 1.0);
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..10000 {
+            let p = aperture.sample(&vec_dist, &mut rng);
+            let cell = 2.0 / Mask::RESOLUTION as f64;
+            assert!(
+                p.x > 0.8 - cell && p.mag_squared() <= 1.0,
+                "{:?}",
+                p.components()
+            );
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use rand::distributions::Uniform;
+use rand::rngs::ThreadRng;
+
+use super::Camera;
+use crate::ray::Ray;
+
+use std::sync::Arc;
+
+/// Arrangement of the two views in a stereo image
+#[derive(Debug, Clone, Copy)]
+pub enum StereoLayout {
+    /// Left eye in the left half, right eye in the right half
+    SideBySide,
+    /// Left eye in the top half, right eye in the bottom half
+    OverUnder,
+}
+
+/// Pair of views for the left and right eyes packed into one image, for VR
+/// headsets and 3D displays
+pub struct Stereo {
+    left: Arc<dyn Camera>,
+    right: Arc<dyn Camera>,
+    layout: StereoLayout,
+}
+
+impl Stereo {
+    /// Each eye's camera is usually the same kind, placed about 0.065 units
+    /// (the human interpupillary distance in meters) apart, and should have
+    /// the aspect ratio of its half of the image
+    pub fn new(left: Arc<dyn Camera>, right: Arc<dyn Camera>, layout: StereoLayout) -> Self {
+        Self {
+            left,
+            right,
+            layout,
+        }
+    }
+}
+
+impl Camera for Stereo {
+    fn get_ray(&self, s: f64, t: f64, vec_dist: &Uniform<f64>, rng: &mut ThreadRng) -> Ray {
+        match self.layout {
+            StereoLayout::SideBySide if s < 0.5 => self.left.get_ray(2.0 * s, t, vec_dist, rng),
+            StereoLayout::SideBySide => self.right.get_ray(2.0 * s - 1.0, t, vec_dist, rng),
+            StereoLayout::OverUnder if t >= 0.5 => {
+                self.left.get_ray(s, 2.0 * t - 1.0, vec_dist, rng)
+            }
+            StereoLayout::OverUnder => self.right.get_ray(s, 2.0 * t, vec_dist, rng),
+        }
+    }
+}
@@ -1,25 +1,81 @@
 use crate::material::Material;
 use crate::ray::Ray;
-use crate::vec::{Point, Vec3};
+use crate::vec::{Frame, Point, Vec3};
 
 use std::ops::RangeInclusive;
 use std::sync::Arc;
 
+pub mod aabb;
+pub mod cone;
+pub mod constant_medium;
+pub mod csg;
+pub mod cuboid;
+pub mod cylinder;
+pub mod disk;
+pub mod grid_volume;
+pub mod keyframed;
+pub mod plane;
+pub mod quad;
+pub mod sdf;
 pub mod sphere;
-pub use sphere::Sphere;
+pub mod torus;
+pub mod transformed;
+
+pub use aabb::Aabb;
+pub use cone::Cone;
+pub use constant_medium::ConstantMedium;
+pub use csg::Csg;
+pub use cuboid::Cuboid;
+pub use cylinder::Cylinder;
+pub use disk::Disk;
+pub use grid_volume::{GridVolume, VoxelGrid};
+pub use keyframed::Keyframed;
+pub use plane::Plane;
+pub use quad::{Quad, Rect};
+pub use sdf::{DistanceField, Sdf};
+pub use sphere::{MovingSphere, Sphere};
+pub use torus::Torus;
+pub use transformed::Transformed;
 
 /// Record of the hit
+#[derive(Clone)]
 pub struct Record {
     pub point: Point,
     pub normal: Vec3,
     pub material: Arc<dyn Material>,
     pub distance: f64,
     pub front_face: bool,
+    /// Surface texture coordinates, each in 0.0..=1.0
+    pub u: f64,
+    pub v: f64,
+    /// Partial derivative of the surface point with respect to `u`
+    pub dpdu: Vec3,
+    /// Partial derivative of the surface point with respect to `v`
+    pub dpdv: Vec3,
 }
 
 /// Something that may be hit by a ray
-pub trait Hittable {
+pub trait Hittable: Send + Sync {
     fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record>;
+
+    /// Every surface crossing within the range, nearest first. For a closed
+    /// object, crossings alternate between entering (`front_face`) and
+    /// leaving.
+    fn hits(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Vec<Record> {
+        let mut hits = Vec::new();
+        let mut start = *t_range.start();
+        while let Some(hit) = self.hit(r, start..=*t_range.end()) {
+            // Step past the crossing so it is not found again
+            start = hit.distance + 1.0e-6 * hit.distance.abs().max(1.0);
+            hits.push(hit);
+        }
+        hits
+    }
+
+    /// Box enclosing the object, or `None` if it is unbounded
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
 }
 
 /// Store a list of hittable objects
@@ -27,9 +83,9 @@ pub trait Hittable {
 pub struct List(Vec<Box<dyn Hittable>>);
 
 impl List {
-    pub fn add<T: 'static>(&mut self, hittable: T)
+    pub fn add<T>(&mut self, hittable: T)
     where
-        T: Hittable,
+        T: Hittable + 'static,
     {
         self.0.push(Box::new(hittable));
     }
@@ -49,6 +105,12 @@ impl Hittable for List {
             })
             .0
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let mut boxes = self.0.iter().map(|hittable| hittable.bounding_box());
+        let first = boxes.next()??;
+        boxes.try_fold(first, |bounds, other| Some(bounds.surrounding(&other?)))
+    }
 }
 
 impl Record {
@@ -57,6 +119,8 @@ impl Record {
         point: Point,
         outward_normal: Vec3,
         distance: f64,
+        (u, v): (f64, f64),
+        (dpdu, dpdv): (Vec3, Vec3),
         material: Arc<dyn Material>,
     ) -> Self {
         let front_face = r.direction.dot(outward_normal) < 0.0;
@@ -71,6 +135,51 @@ impl Record {
             material: material.clone(),
             distance,
             front_face,
+            u,
+            v,
+            dpdu,
+            dpdv,
+        }
+    }
+
+    /// Scattering event inside a participating medium, where the normal and
+    /// surface coordinates are meaningless and only given arbitrary values
+    pub fn volume(point: Point, distance: f64, material: Arc<dyn Material>) -> Self {
+        Self {
+            point,
+            normal: Vec3::new(1.0, 0.0, 0.0),
+            material,
+            distance,
+            front_face: true,
+            u: 0.0,
+            v: 0.0,
+            dpdu: Vec3::new(0.0, 1.0, 0.0),
+            dpdv: Vec3::new(0.0, 0.0, 1.0),
         }
     }
+
+    /// Shading frame around the normal, aligned with the `u` direction
+    pub fn frame(&self) -> Frame {
+        Frame::new(self.normal, self.dpdu)
+    }
+}
+
+/// Real roots of `a t² + b t + c` in ascending order, or of `b t + c` when the
+/// quadratic term vanishes. The single root of a linear equation is paired
+/// with NaN, which no range contains, so it is only counted once.
+fn quadratic_roots(a: f64, b: f64, c: f64) -> Option<[f64; 2]> {
+    if a.abs() < 1.0e-12 {
+        if b == 0.0 {
+            return None;
+        }
+        return Some([-c / b, f64::NAN]);
+    }
+
+    let discriminant = b.powi(2) - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let sqrtd = discriminant.sqrt();
+    let (t0, t1) = ((-b - sqrtd) / (2.0 * a), (-b + sqrtd) / (2.0 * a));
+    Some([t0.min(t1), t0.max(t1)])
 }
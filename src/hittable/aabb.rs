@@ -0,0 +1,97 @@
+use crate::ray::Ray;
+use crate::vec::{Mat4, Point, Vec3};
+
+/// Axis-aligned bounding box
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Aabb {
+    /// Thickness given to boxes around flat objects, so rays still hit them
+    const PADDING: f64 = 1.0e-4;
+
+    /// Smallest box containing both corners, in any order
+    pub fn new(a: Point, b: Point) -> Self {
+        Self::from_points(&[a, b])
+    }
+
+    /// Smallest box containing all the points, padded along any axis where it
+    /// would be flat
+    pub fn from_points(points: &[Point]) -> Self {
+        let mut min = Point::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut max = -min;
+        for p in points {
+            min = Point::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+        }
+        let pad = |min: &mut f64, max: &mut f64| {
+            if *max - *min < Self::PADDING {
+                *min -= Self::PADDING / 2.0;
+                *max += Self::PADDING / 2.0;
+            }
+        };
+        pad(&mut min.x, &mut max.x);
+        pad(&mut min.y, &mut max.y);
+        pad(&mut min.z, &mut max.z);
+        Self { min, max }
+    }
+
+    /// Smallest box containing both boxes
+    pub fn surrounding(&self, other: &Self) -> Self {
+        Self::from_points(&[self.min, self.max, other.min, other.max])
+    }
+
+    /// The eight corner points
+    pub fn corners(&self) -> [Point; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Point::new(a.x, a.y, a.z),
+            Point::new(b.x, a.y, a.z),
+            Point::new(a.x, b.y, a.z),
+            Point::new(b.x, b.y, a.z),
+            Point::new(a.x, a.y, b.z),
+            Point::new(b.x, a.y, b.z),
+            Point::new(a.x, b.y, b.z),
+            Point::new(b.x, b.y, b.z),
+        ]
+    }
+
+    /// Box around this box after a transform
+    pub fn transformed(&self, transform: &Mat4) -> Self {
+        let corners = self.corners().map(|p| transform.transform_point(p));
+        Self::from_points(&corners)
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// Parametric distances where the ray enters and leaves the box, by the
+    /// slab method
+    pub fn clip(&self, r: &Ray) -> Option<(f64, f64)> {
+        let origin = [r.origin.x, r.origin.y, r.origin.z];
+        let direction = [r.direction.x, r.direction.y, r.direction.z];
+        let min = [self.min.x, self.min.y, self.min.z];
+        let max = [self.max.x, self.max.y, self.max.z];
+
+        let mut t_enter = f64::NEG_INFINITY;
+        let mut t_leave = f64::INFINITY;
+        for axis in 0..3 {
+            let inverse = 1.0 / direction[axis];
+            let mut t0 = (min[axis] - origin[axis]) * inverse;
+            let mut t1 = (max[axis] - origin[axis]) * inverse;
+            if inverse < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_enter = t_enter.max(t0);
+            t_leave = t_leave.min(t1);
+        }
+        if t_enter < t_leave {
+            Some((t_enter, t_leave))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use super::{quadratic_roots, Aabb, Disk, Hittable, Record};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vec::{Point, Vec3};
+
+use std::f64::consts::PI;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Circular cone standing upright along the Y axis with its apex at the top,
+/// optionally closed at the base
+pub struct Cone {
+    /// Center of the circular base
+    base: Point,
+    radius: f64,
+    height: f64,
+    /// Disk closing the base, for a solid cone
+    cap: Option<Disk>,
+    material: Arc<dyn Material>,
+}
+
+impl Cone {
+    /// Open cone, e.g. a lampshade or funnel
+    ///
+    /// Panics unless the radius and height are positive
+    pub fn new(base: Point, radius: f64, height: f64, material: Arc<dyn Material>) -> Self {
+        assert!(
+            radius > 0.0 && height > 0.0,
+            "cone needs a positive radius and height"
+        );
+        Self {
+            base,
+            radius,
+            height,
+            cap: None,
+            material,
+        }
+    }
+
+    /// Solid cone closed by a flat base
+    pub fn capped(base: Point, radius: f64, height: f64, material: Arc<dyn Material>) -> Self {
+        let cap = Disk::new(base, Vec3::new(0.0, -1.0, 0.0), radius, material.clone());
+        Self {
+            cap: Some(cap),
+            ..Self::new(base, radius, height, material)
+        }
+    }
+
+    /// Distances to the side within the range, nearest first
+    fn side_distances(&self, r: &Ray, t_range: RangeInclusive<f64>) -> impl Iterator<Item = f64> {
+        // Points on the side satisfy x² + z² = (k (h - y))², where k is the
+        // ratio of radius to height
+        let o = r.origin - self.base;
+        let d = r.direction;
+        let k2 = (self.radius / self.height).powi(2);
+        let below_apex = self.height - o.y;
+        let a = d.x.powi(2) + d.z.powi(2) - k2 * d.y.powi(2);
+        let b = 2.0 * (o.x * d.x + o.z * d.z + k2 * below_apex * d.y);
+        let c = o.x.powi(2) + o.z.powi(2) - k2 * below_apex.powi(2);
+
+        let height = self.height;
+        quadratic_roots(a, b, c)
+            .into_iter()
+            .flatten()
+            .filter(move |&t| {
+                let y = o.y + t * d.y;
+                t_range.contains(&t) && (0.0..=height).contains(&y)
+            })
+    }
+
+    fn side_record(&self, r: &Ray, distance: f64) -> Record {
+        let point = r.at(distance);
+        let p = point - self.base;
+        let rho = (p.x.powi(2) + p.z.powi(2)).sqrt();
+        let radial = if rho > 0.0 {
+            Vec3::new(p.x, 0.0, p.z) / rho
+        } else {
+            // The apex has no well-defined direction around the axis
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let outward_normal = (self.height * radial + Vec3::new(0.0, self.radius, 0.0)).unit();
+
+        // Texture coordinates wrap around the axis like a sphere's, with `v`
+        // running from base to apex
+        let u = ((-p.z).atan2(p.x) + PI) / (2.0 * PI);
+        let v = p.y / self.height;
+        let dpdu = 2.0 * PI * Vec3::new(p.z, 0.0, -p.x);
+        let dpdv = Vec3::new(0.0, self.height, 0.0) - self.radius * radial;
+
+        Record::new(
+            r,
+            point,
+            outward_normal,
+            distance,
+            (u, v),
+            (dpdu, dpdv),
+            self.material.clone(),
+        )
+    }
+
+    fn hit_side(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        let distance = self.side_distances(r, t_range).next()?;
+        Some(self.side_record(r, distance))
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        let side = self.hit_side(r, t_range.clone());
+        let end = side.as_ref().map_or(*t_range.end(), |hit| hit.distance);
+        match &self.cap {
+            Some(cap) => cap.hit(r, *t_range.start()..=end).or(side),
+            None => side,
+        }
+    }
+
+    fn hits(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Vec<Record> {
+        let mut hits: Vec<Record> = self
+            .side_distances(r, t_range.clone())
+            .map(|distance| self.side_record(r, distance))
+            .collect();
+        if let Some(cap) = &self.cap {
+            hits.extend(cap.hit(r, t_range));
+        }
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vec3::new(self.radius, 0.0, self.radius);
+        let apex = self.base + Vec3::new(0.0, self.height, 0.0);
+        Some(Aabb::new(self.base - extent, apex + extent))
+    }
+}
@@ -0,0 +1,68 @@
+use super::{Aabb, Hittable, Record};
+use crate::material::{Isotropic, Material};
+use crate::ray::Ray;
+use crate::vec::Color;
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Fog or smoke of uniform density filling a closed boundary
+pub struct ConstantMedium {
+    boundary: Arc<dyn Hittable>,
+    negative_inverse_density: f64,
+    phase_function: Arc<dyn Material>,
+}
+
+impl ConstantMedium {
+    /// Medium scattering light equally in all directions
+    pub fn new(boundary: Arc<dyn Hittable>, density: f64, albedo: Color) -> Self {
+        Self::with_phase_function(boundary, density, Arc::new(Isotropic::new(albedo)))
+    }
+
+    pub fn with_phase_function(
+        boundary: Arc<dyn Hittable>,
+        density: f64,
+        phase_function: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            boundary,
+            negative_inverse_density: -1.0 / density,
+            phase_function,
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        // Where the ray enters and leaves the boundary, even if it starts inside
+        let entry = self.boundary.hit(r, f64::NEG_INFINITY..=f64::INFINITY)?;
+        let exit = self
+            .boundary
+            .hit(r, entry.distance + 0.0001..=f64::INFINITY)?;
+
+        let t_entry = entry.distance.max(*t_range.start()).max(0.0);
+        let t_exit = exit.distance.min(*t_range.end());
+        if t_entry >= t_exit {
+            return None;
+        }
+
+        // Sample the free-flight distance to the next scattering event
+        let ray_length = r.direction.mag();
+        let distance_inside = (t_exit - t_entry) * ray_length;
+        let hit_distance = self.negative_inverse_density * rand::random::<f64>().ln();
+        if hit_distance > distance_inside {
+            return None;
+        }
+
+        let distance = t_entry + hit_distance / ray_length;
+        Some(Record::volume(
+            r.at(distance),
+            distance,
+            self.phase_function.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
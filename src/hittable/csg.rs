@@ -0,0 +1,227 @@
+use super::{Aabb, Hittable, Record};
+use crate::ray::Ray;
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl Operation {
+    fn contains(&self, inside_a: bool, inside_b: bool) -> bool {
+        match self {
+            Operation::Union => inside_a || inside_b,
+            Operation::Intersection => inside_a && inside_b,
+            Operation::Difference => inside_a && !inside_b,
+        }
+    }
+}
+
+/// [Constructive solid geometry](https://en.wikipedia.org/wiki/Constructive_solid_geometry)
+/// combination of two objects, e.g. a lens as the intersection of two spheres
+/// or a hollow ball as the difference of two.
+///
+/// Whether a ray is inside each object is worked out by counting how often it
+/// crosses its surface, so both must be closed: spheres, boxes, capped
+/// cylinders and cones, tori or other CSG objects. Open surfaces such as
+/// planes, quads or uncapped cylinders give meaningless results.
+pub struct Csg {
+    operation: Operation,
+    a: Arc<dyn Hittable>,
+    b: Arc<dyn Hittable>,
+}
+
+impl Csg {
+    /// Space inside either object
+    pub fn union(a: Arc<dyn Hittable>, b: Arc<dyn Hittable>) -> Self {
+        Self {
+            operation: Operation::Union,
+            a,
+            b,
+        }
+    }
+
+    /// Space inside both objects
+    pub fn intersection(a: Arc<dyn Hittable>, b: Arc<dyn Hittable>) -> Self {
+        Self {
+            operation: Operation::Intersection,
+            a,
+            b,
+        }
+    }
+
+    /// Space inside `a` but not `b`. Surfaces carved out by `b` take the
+    /// material of `a`, so the result is a single solid.
+    pub fn difference(a: Arc<dyn Hittable>, b: Arc<dyn Hittable>) -> Self {
+        Self {
+            operation: Operation::Difference,
+            a,
+            b,
+        }
+    }
+
+    /// Crossings of the combined surface from `t_start` onward
+    fn boundaries(&self, r: &Ray, t_start: f64) -> Vec<Record> {
+        // Crossings beyond the end of the range are still needed to tell
+        // whether the range starts inside an object. Rays leave a closed
+        // object as often as they enter, so an odd count means starting
+        // inside. Counting, rather than trusting `front_face`, keeps tangent
+        // rays right, whose two crossings may both face either way.
+        let hits_a = self.a.hits(r, t_start..=f64::INFINITY);
+        let hits_b = self.b.hits(r, t_start..=f64::INFINITY);
+        let starts_inside = |hits: &[Record]| hits.len() % 2 == 1;
+        let mut inside_a = starts_inside(&hits_a);
+        let mut inside_b = starts_inside(&hits_b);
+        // Material of the part of `a` the ray is in or about to enter
+        let mut material_a = hits_a.first().map(|hit| hit.material.clone());
+
+        let mut events: Vec<(bool, Record)> = hits_a
+            .into_iter()
+            .map(|hit| (true, hit))
+            .chain(hits_b.into_iter().map(|hit| (false, hit)))
+            .collect();
+        events.sort_by(|(_, x), (_, y)| x.distance.total_cmp(&y.distance));
+
+        let mut boundaries: Vec<Record> = Vec::new();
+        for (from_a, mut hit) in events {
+            let was_inside = self.operation.contains(inside_a, inside_b);
+            if from_a {
+                inside_a = !inside_a;
+                material_a = Some(hit.material.clone());
+            } else {
+                inside_b = !inside_b;
+            }
+            let is_inside = self.operation.contains(inside_a, inside_b);
+            if was_inside == is_inside {
+                continue;
+            }
+
+            // The record's normal already faces the ray; only which side of
+            // the combined surface the ray is on needs updating
+            hit.front_face = is_inside;
+            // Leaving and re-entering at the same point, as where a ray grazes
+            // another surface, is no crossing at all
+            let coincident = boundaries.last().is_some_and(|last| {
+                (hit.distance - last.distance).abs() <= 1.0e-9 * hit.distance.abs().max(1.0)
+            });
+            if coincident {
+                boundaries.pop();
+                continue;
+            }
+
+            if let (Operation::Difference, false, Some(material)) =
+                (self.operation, from_a, &material_a)
+            {
+                hit.material = material.clone();
+            }
+            boundaries.push(hit);
+        }
+        boundaries
+    }
+}
+
+impl Hittable for Csg {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        self.hits(r, t_range).into_iter().next()
+    }
+
+    fn hits(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Vec<Record> {
+        let mut boundaries = self.boundaries(r, *t_range.start());
+        boundaries.retain(|hit| hit.distance <= *t_range.end());
+        boundaries
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        match self.operation {
+            Operation::Union => Some(self.a.bounding_box()?.surrounding(&self.b.bounding_box()?)),
+            // The result lies within `a`
+            Operation::Intersection | Operation::Difference => self.a.bounding_box(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::Sphere;
+    use crate::material::Lambertian;
+    use crate::vec::{Color, Point, Vec3};
+
+    fn sphere(center: Point, radius: f64) -> Arc<dyn Hittable> {
+        let material = Arc::new(Lambertian::new(Color::ONE));
+        Arc::new(Sphere::new(center, radius, material))
+    }
+
+    /// Distances along a ray down the X axis where it enters and leaves the
+    /// combination of two unit balls overlapping around the origin, checking
+    /// that each normal points out of the result
+    fn crossings(combine: fn(Arc<dyn Hittable>, Arc<dyn Hittable>) -> Csg) -> Vec<f64> {
+        let csg = combine(
+            sphere(Point::new(-0.5, 0.0, 0.0), 1.0),
+            sphere(Point::new(0.5, 0.0, 0.0), 1.0),
+        );
+        let r = Ray::new(Point::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+
+        let hits = csg.hits(&r, 0.0..=f64::INFINITY);
+        for (i, hit) in hits.iter().enumerate() {
+            let entering = i % 2 == 0;
+            assert_eq!(hit.front_face, entering);
+            let outward = if hit.front_face {
+                hit.normal
+            } else {
+                -hit.normal
+            };
+            let expected = if entering { -1.0 } else { 1.0 };
+            assert!(
+                (outward.x - expected).abs() < 1e-9,
+                "{:?}",
+                outward.components()
+            );
+        }
+        hits.iter().map(|hit| hit.distance).collect()
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn union_spans_both_balls() {
+        assert_close(&crossings(Csg::union), &[3.5, 6.5]);
+    }
+
+    #[test]
+    fn intersection_spans_the_overlap() {
+        assert_close(&crossings(Csg::intersection), &[4.5, 5.5]);
+    }
+
+    #[test]
+    fn difference_stops_at_the_second_ball() {
+        assert_close(&crossings(Csg::difference), &[3.5, 4.5]);
+    }
+
+    #[test]
+    fn tangent_rays_keep_inside_and_outside() {
+        // Bite out of the top of a ball, which the ray just grazes
+        let csg = Csg::difference(
+            sphere(Point::ZERO, 1.0),
+            sphere(Point::new(0.0, 1.0, 0.0), 0.5),
+        );
+        let r = Ray::new(Point::new(-2.0, 0.5, 0.0), Vec3::new(1.0, 0.0, 0.0));
+
+        let hits = csg.hits(&r, 0.0..=f64::INFINITY);
+        let distances: Vec<f64> = hits.iter().map(|hit| hit.distance).collect();
+        let half_chord = 0.75_f64.sqrt();
+        assert_eq!(distances.len(), 2, "{:?}", distances);
+        assert!((distances[0] - (2.0 - half_chord)).abs() < 1e-9);
+        assert!((distances[1] - (2.0 + half_chord)).abs() < 1e-9);
+        assert!(hits[0].front_face && !hits[1].front_face);
+    }
+}
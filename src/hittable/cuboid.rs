@@ -0,0 +1,66 @@
+use super::{Aabb, Hittable, List, Quad, Record};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vec::{Point, Vec3};
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Closed axis-aligned box made of six quads facing outward
+pub struct Cuboid {
+    sides: List,
+    bounds: Aabb,
+}
+
+impl Cuboid {
+    /// Box with opposite corners `a` and `b`
+    pub fn new(a: Point, b: Point, material: Arc<dyn Material>) -> Self {
+        let bounds = Aabb::new(a, b);
+        let (min, max) = (bounds.min, bounds.max);
+        let size = max - min;
+        let dx = Vec3::new(size.x, 0.0, 0.0);
+        let dy = Vec3::new(0.0, size.y, 0.0);
+        let dz = Vec3::new(0.0, 0.0, size.z);
+
+        let mut sides = List::default();
+        // Front, right, back, left, top, bottom
+        sides.add(Quad::new(
+            Point::new(min.x, min.y, max.z),
+            dx,
+            dy,
+            material.clone(),
+        ));
+        sides.add(Quad::new(
+            Point::new(max.x, min.y, max.z),
+            -dz,
+            dy,
+            material.clone(),
+        ));
+        sides.add(Quad::new(
+            Point::new(max.x, min.y, min.z),
+            -dx,
+            dy,
+            material.clone(),
+        ));
+        sides.add(Quad::new(min, dz, dy, material.clone()));
+        sides.add(Quad::new(
+            Point::new(min.x, max.y, max.z),
+            dx,
+            -dz,
+            material.clone(),
+        ));
+        sides.add(Quad::new(min, dx, dz, material));
+
+        Self { sides, bounds }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        self.sides.hit(r, t_range)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
@@ -0,0 +1,129 @@
+use super::{quadratic_roots, Aabb, Disk, Hittable, Record};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vec::{Point, Vec3};
+
+use std::f64::consts::PI;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Circular tube standing upright along the Y axis, optionally closed at both
+/// ends
+pub struct Cylinder {
+    /// Center of the bottom end
+    base: Point,
+    radius: f64,
+    height: f64,
+    /// Bottom and top end disks, for a closed cylinder
+    caps: Option<[Disk; 2]>,
+    material: Arc<dyn Material>,
+}
+
+impl Cylinder {
+    /// Open tube, e.g. a pipe
+    ///
+    /// Panics unless the radius and height are positive
+    pub fn new(base: Point, radius: f64, height: f64, material: Arc<dyn Material>) -> Self {
+        assert!(
+            radius > 0.0 && height > 0.0,
+            "cylinder needs a positive radius and height"
+        );
+        Self {
+            base,
+            radius,
+            height,
+            caps: None,
+            material,
+        }
+    }
+
+    /// Solid cylinder closed by flat ends
+    pub fn capped(base: Point, radius: f64, height: f64, material: Arc<dyn Material>) -> Self {
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        let caps = [
+            Disk::new(base, -up, radius, material.clone()),
+            Disk::new(base + height * up, up, radius, material.clone()),
+        ];
+        Self {
+            caps: Some(caps),
+            ..Self::new(base, radius, height, material)
+        }
+    }
+
+    /// Distances to the side within the range, nearest first
+    fn side_distances(&self, r: &Ray, t_range: RangeInclusive<f64>) -> impl Iterator<Item = f64> {
+        let o = r.origin - self.base;
+        let d = r.direction;
+        let a = d.x.powi(2) + d.z.powi(2);
+        let b = 2.0 * (o.x * d.x + o.z * d.z);
+        let c = o.x.powi(2) + o.z.powi(2) - self.radius.powi(2);
+
+        let height = self.height;
+        quadratic_roots(a, b, c)
+            .into_iter()
+            .flatten()
+            .filter(move |&t| {
+                let y = o.y + t * d.y;
+                t_range.contains(&t) && (0.0..=height).contains(&y)
+            })
+    }
+
+    fn side_record(&self, r: &Ray, distance: f64) -> Record {
+        let point = r.at(distance);
+        let p = point - self.base;
+        let outward_normal = Vec3::new(p.x, 0.0, p.z) / self.radius;
+
+        // Texture coordinates wrap around the axis like a sphere's, with `v`
+        // running from bottom to top
+        let u = ((-p.z).atan2(p.x) + PI) / (2.0 * PI);
+        let v = p.y / self.height;
+        let dpdu = 2.0 * PI * Vec3::new(p.z, 0.0, -p.x);
+        let dpdv = Vec3::new(0.0, self.height, 0.0);
+
+        Record::new(
+            r,
+            point,
+            outward_normal,
+            distance,
+            (u, v),
+            (dpdu, dpdv),
+            self.material.clone(),
+        )
+    }
+
+    fn hit_side(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        let distance = self.side_distances(r, t_range).next()?;
+        Some(self.side_record(r, distance))
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        let mut closest = self.hit_side(r, t_range.clone());
+        for cap in self.caps.iter().flatten() {
+            let end = closest.as_ref().map_or(*t_range.end(), |hit| hit.distance);
+            if let Some(hit) = cap.hit(r, *t_range.start()..=end) {
+                closest = Some(hit);
+            }
+        }
+        closest
+    }
+
+    fn hits(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Vec<Record> {
+        let mut hits: Vec<Record> = self
+            .side_distances(r, t_range.clone())
+            .map(|distance| self.side_record(r, distance))
+            .collect();
+        for cap in self.caps.iter().flatten() {
+            hits.extend(cap.hit(r, t_range.clone()));
+        }
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vec3::new(self.radius, 0.0, self.radius);
+        let top = self.base + Vec3::new(0.0, self.height, 0.0);
+        Some(Aabb::new(self.base - extent, top + extent))
+    }
+}
@@ -0,0 +1,82 @@
+use super::{Aabb, Hittable, Record};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vec::{Frame, Point, Vec3};
+
+use std::f64::consts::PI;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Flat circle
+pub struct Disk {
+    center: Point,
+    radius: f64,
+    /// Axes in the plane of the disk, with the unit normal as `n`
+    frame: Frame,
+    material: Arc<dyn Material>,
+}
+
+impl Disk {
+    pub fn new(center: Point, normal: Vec3, radius: f64, material: Arc<dyn Material>) -> Self {
+        Self {
+            center,
+            radius,
+            frame: Frame::new(normal.unit(), Vec3::new(1.0, 0.0, 0.0)),
+            material,
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        let denominator = self.frame.n.dot(r.direction);
+        if denominator.abs() < 1.0e-8 {
+            // Parallel to the disk
+            return None;
+        }
+
+        let distance = self.frame.n.dot(self.center - r.origin) / denominator;
+        if !t_range.contains(&distance) {
+            return None;
+        }
+
+        let point = r.at(distance);
+        let local = self.frame.to_local(point - self.center);
+        let rho = (local.x.powi(2) + local.y.powi(2)).sqrt();
+        if rho > self.radius {
+            return None;
+        }
+
+        // `u` is the angle around the center and `v` the fraction of the radius
+        let phi = local.y.atan2(local.x).rem_euclid(2.0 * PI);
+        let radial = if rho > 0.0 {
+            (local.x * self.frame.s + local.y * self.frame.t) / rho
+        } else {
+            self.frame.s
+        };
+        let dpdu = 2.0 * PI * rho * self.frame.n.cross(radial);
+        let dpdv = self.radius * radial;
+
+        Some(Record::new(
+            r,
+            point,
+            self.frame.n,
+            distance,
+            (phi / (2.0 * PI), rho / self.radius),
+            (dpdu, dpdv),
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let n = self.frame.n;
+        // Half-extent of the circle along each axis
+        let extent = self.radius
+            * Vec3::new(
+                (1.0 - n.x.powi(2)).max(0.0).sqrt(),
+                (1.0 - n.y.powi(2)).max(0.0).sqrt(),
+                (1.0 - n.z.powi(2)).max(0.0).sqrt(),
+            );
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+}
@@ -0,0 +1,463 @@
+use super::{Aabb, Hittable, Record};
+use crate::material::{HenyeyGreenstein, Material, Scatter};
+use crate::ray::Ray;
+use crate::vec::{Color, Point};
+
+use rand::distributions::Uniform;
+use rand::rngs::ThreadRng;
+
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Dense 3D grid of scalar values spanning an axis-aligned box
+pub struct VoxelGrid {
+    resolution: [usize; 3],
+    bounds: Aabb,
+    /// Values with X varying fastest, then Y, then Z
+    data: Vec<f64>,
+    max_value: f64,
+}
+
+impl VoxelGrid {
+    /// Panics unless there is at least one voxel along each axis, and a value
+    /// for every voxel
+    pub fn new(resolution: [usize; 3], min: Point, max: Point, data: Vec<f64>) -> Self {
+        assert!(
+            resolution.iter().all(|&size| size > 0),
+            "voxel grid needs at least one voxel along each axis"
+        );
+        assert_eq!(
+            data.len(),
+            resolution[0] * resolution[1] * resolution[2],
+            "voxel count must match resolution"
+        );
+        let max_value = data.iter().cloned().fold(0.0, f64::max);
+        Self {
+            resolution,
+            bounds: Aabb::new(min, max),
+            data,
+            max_value,
+        }
+    }
+
+    /// Read a grid in the [Mitsuba `.vol`](https://mitsuba.readthedocs.io/en/latest/src/generated/plugins_volumes.html#grid-based-volume-data-source-gridvolume)
+    /// format: little-endian 32-bit float voxels with a header giving the
+    /// resolution, channel count and bounding box. Only the first channel is
+    /// kept.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic[..3] != b"VOL" || magic[3] != 3 {
+            return Err(invalid("not a version 3 VOL file"));
+        }
+
+        let mut read_i32 = || -> io::Result<i32> {
+            let mut bytes = [0; 4];
+            reader.read_exact(&mut bytes)?;
+            Ok(i32::from_le_bytes(bytes))
+        };
+        if read_i32()? != 1 {
+            return Err(invalid("only 32-bit float VOL data is supported"));
+        }
+        let mut dimension = || -> io::Result<usize> {
+            usize::try_from(read_i32()?).map_err(|_| invalid("negative VOL dimension"))
+        };
+        let resolution = [dimension()?, dimension()?, dimension()?];
+        let channels = dimension()?;
+        if channels == 0 || resolution.contains(&0) {
+            return Err(invalid("VOL file has no voxels"));
+        }
+        let voxels = resolution
+            .iter()
+            .try_fold(1_usize, |voxels, &size| voxels.checked_mul(size));
+        let needed = voxels
+            .and_then(|voxels| voxels.checked_mul(channels))
+            .and_then(|floats| floats.checked_add(6));
+        let (voxels, needed) = match (voxels, needed) {
+            (Some(voxels), Some(needed)) => (voxels, needed),
+            _ => return Err(invalid("VOL dimensions overflow")),
+        };
+
+        let mut floats = Vec::new();
+        reader.read_to_end(&mut floats)?;
+        let floats: Vec<f64> = floats
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+            .collect();
+
+        if floats.len() < needed {
+            return Err(invalid("VOL file is truncated"));
+        }
+        let min = Point::new(floats[0], floats[1], floats[2]);
+        let max = Point::new(floats[3], floats[4], floats[5]);
+        let data = floats[6..]
+            .chunks_exact(channels)
+            .take(voxels)
+            .map(|voxel| voxel[0])
+            .collect();
+
+        Ok(Self::new(resolution, min, max, data))
+    }
+
+    /// Largest value anywhere in the grid
+    pub fn max_value(&self) -> f64 {
+        self.max_value
+    }
+
+    /// Trilinearly interpolated value at a point, treating voxel values as
+    /// samples at the voxel centers. Zero outside the grid bounds.
+    pub fn value(&self, p: &Point) -> f64 {
+        let extent = self.bounds.size();
+        let relative = *p - self.bounds.min;
+        let coordinates = [
+            relative.x / extent.x,
+            relative.y / extent.y,
+            relative.z / extent.z,
+        ];
+        if coordinates.iter().any(|&c| !(0.0..=1.0).contains(&c)) {
+            return 0.0;
+        }
+
+        // Lower corner index and interpolation weight along each axis
+        let mut lower = [0; 3];
+        let mut weight = [0.0; 3];
+        for axis in 0..3 {
+            let size = self.resolution[axis];
+            let x = (coordinates[axis] * size as f64 - 0.5).clamp(0.0, (size - 1) as f64);
+            lower[axis] = (x.floor() as usize).min(size.saturating_sub(2));
+            weight[axis] = x - lower[axis] as f64;
+        }
+
+        let voxel = |x: usize, y: usize, z: usize| {
+            let x = x.min(self.resolution[0] - 1);
+            let y = y.min(self.resolution[1] - 1);
+            let z = z.min(self.resolution[2] - 1);
+            self.data[(z * self.resolution[1] + y) * self.resolution[0] + x]
+        };
+
+        let mut sum = 0.0;
+        for corner in 0..8 {
+            let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+            let mut w = 1.0;
+            for axis in 0..3 {
+                w *= if offset[axis] == 1 {
+                    weight[axis]
+                } else {
+                    1.0 - weight[axis]
+                };
+            }
+            sum += w * voxel(
+                lower[0] + offset[0],
+                lower[1] + offset[1],
+                lower[2] + offset[2],
+            );
+        }
+        sum
+    }
+}
+
+/// Smoke, clouds or fire with density, and optionally emission, read from
+/// voxel grids
+pub struct GridVolume {
+    density: VoxelGrid,
+    /// Extinction coefficient per unit of grid density
+    density_scale: f64,
+    phase_function: Arc<dyn Material>,
+    emission: Option<Emission>,
+    /// Rate of tentative collisions per unit length, at least the largest
+    /// extinction coefficient
+    majorant: f64,
+    /// Region tracked, covering the density and any emission grid
+    bounds: Aabb,
+}
+
+/// Light given off by a volume, with the materials reporting it at tentative
+/// collisions
+struct Emission {
+    grid: Arc<VoxelGrid>,
+    /// Phase function that also emits, for real collisions
+    scattering: Arc<dyn Material>,
+    /// Emits and lets the ray continue unchanged, for null collisions
+    passing: Arc<dyn Material>,
+}
+
+impl GridVolume {
+    /// Tentative collisions per crossing of the volume's diagonal, at least,
+    /// when it emits. Emission is sampled at these, so it is found even where
+    /// there is too little density to collide with.
+    const EMISSION_SAMPLES: f64 = 16.0;
+
+    /// Forward-scattering volume with a Henyey-Greenstein phase function
+    pub fn new(density: VoxelGrid, density_scale: f64, albedo: Color, g: f64) -> Self {
+        Self::with_phase_function(
+            density,
+            density_scale,
+            Arc::new(HenyeyGreenstein::new(albedo, g)),
+        )
+    }
+
+    pub fn with_phase_function(
+        density: VoxelGrid,
+        density_scale: f64,
+        phase_function: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            majorant: density_scale * density.max_value(),
+            bounds: density.bounds,
+            density,
+            density_scale,
+            phase_function,
+            emission: None,
+        }
+    }
+
+    /// Make the volume glow, giving off `color` scaled by the values in
+    /// `emission` per unit length traveled through it. Emission does not
+    /// depend on density, so flames can burn without smoke.
+    pub fn with_emission(mut self, emission: VoxelGrid, color: Color) -> Self {
+        self.bounds = self.bounds.surrounding(&emission.bounds);
+        self.majorant = self
+            .majorant
+            .max(Self::EMISSION_SAMPLES / self.bounds.size().mag());
+
+        let grid = Arc::new(emission);
+        let glow = |phase_function| -> Arc<dyn Material> {
+            Arc::new(Glow {
+                grid: grid.clone(),
+                color,
+                majorant: self.majorant,
+                phase_function,
+            })
+        };
+        self.emission = Some(Emission {
+            scattering: glow(Some(self.phase_function.clone())),
+            passing: glow(None),
+            grid: grid.clone(),
+        });
+        self
+    }
+
+    /// Fraction of light passing straight through the volume along the ray
+    /// within `t_range`, by [ratio tracking](https://doi.org/10.1145/2661229.2661292).
+    /// Rendering only needs the collisions from [`hit`](Hittable::hit), but
+    /// this gives a low-variance, unbiased estimate for shadow rays.
+    pub fn transmittance(&self, r: &Ray, t_range: RangeInclusive<f64>) -> f64 {
+        let (t_enter, t_leave) = match self.bounds.clip(r) {
+            Some(clipped) if self.majorant > 0.0 => clipped,
+            _ => return 1.0,
+        };
+        let t_leave = t_leave.min(*t_range.end());
+        let ray_length = r.direction.mag();
+        let mut distance = t_enter.max(*t_range.start());
+        let mut transmittance = 1.0;
+
+        loop {
+            distance -= (1.0 - rand::random::<f64>()).ln() / self.majorant / ray_length;
+            if distance >= t_leave {
+                return transmittance;
+            }
+            let extinction = self.density_scale * self.density.value(&r.at(distance));
+            transmittance *= 1.0 - extinction / self.majorant;
+        }
+    }
+}
+
+impl Hittable for GridVolume {
+    /// [Delta tracking](https://doi.org/10.1145/3084873.3084907) against the
+    /// majorant: tentative collisions are accepted as real with probability
+    /// equal to the local extinction over the majorant. Where the volume
+    /// emits, null collisions are reported too, adding emission over the
+    /// majorant so that emission along the whole path is counted.
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        if self.majorant <= 0.0 {
+            return None;
+        }
+
+        let (t_enter, t_leave) = self.bounds.clip(r)?;
+        let t_leave = t_leave.min(*t_range.end());
+        let ray_length = r.direction.mag();
+        let mut distance = t_enter.max(*t_range.start());
+
+        loop {
+            distance -= (1.0 - rand::random::<f64>()).ln() / self.majorant / ray_length;
+            if distance >= t_leave {
+                return None;
+            }
+
+            let point = r.at(distance);
+            let extinction = self.density_scale * self.density.value(&point);
+            let material = if rand::random::<f64>() * self.majorant < extinction {
+                self.emission
+                    .as_ref()
+                    .map_or(&self.phase_function, |emission| &emission.scattering)
+            } else {
+                match &self.emission {
+                    Some(emission) if emission.grid.value(&point) > 0.0 => &emission.passing,
+                    _ => continue,
+                }
+            };
+            return Some(Record::volume(point, distance, material.clone()));
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
+
+/// Emission seen at a tentative collision, optionally scattering the ray
+struct Glow {
+    grid: Arc<VoxelGrid>,
+    color: Color,
+    majorant: f64,
+    /// Scattering at a real collision, or `None` to pass straight on at a null
+    /// collision
+    phase_function: Option<Arc<dyn Material>>,
+}
+
+impl Material for Glow {
+    fn scatter(
+        &self,
+        r: &Ray,
+        hit: &Record,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Option<Scatter> {
+        match &self.phase_function {
+            Some(phase_function) => phase_function.scatter(r, hit, vec_dist, rng),
+            None => Some(Scatter {
+                ray: r.spawn(hit.point, r.direction),
+                attenuation: Color::ONE,
+            }),
+        }
+    }
+
+    /// Emission over the majorant, whose sum over the tentative collisions
+    /// along a path averages to the emission integrated along it
+    fn emitted(&self, hit: &Record) -> Color {
+        self.grid.value(&hit.point) / self.majorant * self.color
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec::Vec3;
+
+    fn uniform_grid(value: f64) -> VoxelGrid {
+        VoxelGrid::new([2, 2, 2], Point::ZERO, Point::ONE, vec![value; 8])
+    }
+
+    /// Light reaching the origin of a ray through the volume alone
+    fn radiance(
+        volume: &GridVolume,
+        r: &Ray,
+        depth: usize,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Color {
+        match volume.hit(r, 0.0..=f64::INFINITY) {
+            Some(hit) if depth > 0 => {
+                let emitted = hit.material.emitted(&hit);
+                match hit.material.scatter(r, &hit, vec_dist, rng) {
+                    Some(Scatter { ray, attenuation }) => {
+                        emitted
+                            + attenuation.schur(radiance(volume, &ray, depth - 1, vec_dist, rng))
+                    }
+                    None => emitted,
+                }
+            }
+            _ => Color::ZERO,
+        }
+    }
+
+    /// Average brightness seen straight through the unit cube along X
+    fn mean_radiance(volume: &GridVolume) -> f64 {
+        let vec_dist = Uniform::new_inclusive(-1.0, 1.0);
+        let mut rng = rand::thread_rng();
+        let r = Ray::new(Point::new(-1.0, 0.5, 0.5), Vec3::new(1.0, 0.0, 0.0));
+        let samples = 20000;
+        let total = (0..samples).fold(0.0, |total, _| {
+            total + radiance(volume, &r, 1000, &vec_dist, &mut rng).x
+        });
+        total / samples as f64
+    }
+
+    #[test]
+    fn ratio_tracking_follows_beer_lambert() {
+        // Density ramping up from 0 to 1 along X, averaging 0.5, so that it is
+        // mostly below the majorant and each estimate is more than 0 or 1
+        let ramp = VoxelGrid::new(
+            [2, 2, 2],
+            Point::ZERO,
+            Point::ONE,
+            vec![0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0],
+        );
+        let volume = GridVolume::new(ramp, 2.0, Color::ONE, 0.0);
+        let r = Ray::new(Point::new(-1.0, 0.5, 0.5), Vec3::new(1.0, 0.0, 0.0));
+
+        let samples = 10000;
+        let estimates: Vec<f64> = (0..samples)
+            .map(|_| volume.transmittance(&r, 0.0..=f64::INFINITY))
+            .collect();
+        let mean = estimates.iter().sum::<f64>() / samples as f64;
+        let variance =
+            estimates.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (samples - 1) as f64;
+        let standard_error = (variance / samples as f64).sqrt();
+
+        let expected = (-1.0_f64).exp();
+        assert!(
+            (mean - expected).abs() < 5.0 * standard_error,
+            "{} != {} ± {}",
+            mean,
+            expected,
+            5.0 * standard_error
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one voxel")]
+    fn grid_needs_voxels() {
+        VoxelGrid::new([0, 2, 2], Point::ZERO, Point::ONE, Vec::new());
+    }
+
+    #[test]
+    fn open_rejects_overflowing_dimensions() {
+        let path = std::env::temp_dir().join("raytrace_overflowing.vol");
+        let mut header = b"VOL\x03".to_vec();
+        for value in &[1, i32::MAX, i32::MAX, i32::MAX, 1] {
+            header.extend_from_slice(&i32::to_le_bytes(*value));
+        }
+        std::fs::write(&path, header).unwrap();
+        let error = VoxelGrid::open(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn emission_without_density_is_visible() {
+        let volume = GridVolume::new(uniform_grid(0.0), 1.0, Color::ONE, 0.0)
+            .with_emission(uniform_grid(1.0), Color::ONE);
+        // Unit emission over a unit length
+        let mean = mean_radiance(&volume);
+        assert!((mean - 1.0).abs() < 0.03, "{}", mean);
+    }
+
+    #[test]
+    fn emission_is_absorbed_by_density() {
+        let extinction = 0.5;
+        let volume = GridVolume::new(uniform_grid(1.0), extinction, Color::ZERO, 0.0)
+            .with_emission(uniform_grid(1.0), Color::ONE);
+        // Emission along the ray, attenuated on the way out
+        let expected = (1.0 - (-extinction).exp()) / extinction;
+        let mean = mean_radiance(&volume);
+        assert!((mean - expected).abs() < 0.03, "{} != {}", mean, expected);
+    }
+}
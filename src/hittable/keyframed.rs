@@ -0,0 +1,216 @@
+use super::{Aabb, Hittable, Record};
+use crate::animation::Keyframes;
+use crate::ray::Ray;
+use crate::vec::{Mat4, Quat, Vec3};
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Shared object whose placement in the world follows keyframed scale,
+/// rotation and translation, evaluated at each ray's time
+pub struct Keyframed {
+    object: Arc<dyn Hittable>,
+    scale: Keyframes<Vec3>,
+    rotation: Keyframes<Quat>,
+    translation: Keyframes<Vec3>,
+}
+
+impl Keyframed {
+    /// Object left where it is until keys are added
+    pub fn new(object: Arc<dyn Hittable>) -> Self {
+        Self {
+            object,
+            scale: Keyframes::constant(Vec3::ONE),
+            rotation: Keyframes::constant(Quat::IDENTITY),
+            translation: Keyframes::constant(Vec3::ZERO),
+        }
+    }
+
+    /// Scale factors along each axis, applied first. None may be zero.
+    pub fn with_scale(mut self, scale: Keyframes<Vec3>) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Rotation about the origin, applied after scaling
+    pub fn with_rotation(mut self, rotation: Keyframes<Quat>) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Offset applied last
+    pub fn with_translation(mut self, translation: Keyframes<Vec3>) -> Self {
+        self.translation = translation;
+        self
+    }
+
+    fn placement(&self, time: f64) -> Placement {
+        let scale = self.scale.at(time);
+        Placement {
+            scale,
+            inverse_scale: Vec3::new(1.0 / scale.x, 1.0 / scale.y, 1.0 / scale.z),
+            rotation: self.rotation.at(time).unit(),
+            translation: self.translation.at(time),
+        }
+    }
+
+    pub fn transform(&self, time: f64) -> Mat4 {
+        Mat4::scale(self.scale.at(time))
+            .then(self.rotation.at(time).to_mat4())
+            .then(Mat4::translate(self.translation.at(time)))
+    }
+}
+
+/// Scale, rotation and translation at one time, each undone in turn, which is
+/// far cheaper than inverting the combined matrix for every ray
+struct Placement {
+    scale: Vec3,
+    inverse_scale: Vec3,
+    rotation: Quat,
+    translation: Vec3,
+}
+
+impl Placement {
+    fn to_local(&self, r: &Ray) -> Ray {
+        let unrotation = self.rotation.conjugate();
+        // The direction is not normalized, so distances along the object
+        // space ray match those along the world space ray
+        r.spawn(
+            unrotation
+                .rotate(r.origin - self.translation)
+                .schur(self.inverse_scale),
+            unrotation.rotate(r.direction).schur(self.inverse_scale),
+        )
+    }
+
+    fn to_world(&self, mut hit: Record) -> Record {
+        hit.point = self.rotation.rotate(hit.point.schur(self.scale)) + self.translation;
+        // Normals scale inversely to keep perpendicular to the surface
+        hit.normal = self
+            .rotation
+            .rotate(hit.normal.schur(self.inverse_scale))
+            .unit();
+        hit.dpdu = self.rotation.rotate(hit.dpdu.schur(self.scale));
+        hit.dpdv = self.rotation.rotate(hit.dpdv.schur(self.scale));
+        hit
+    }
+}
+
+impl Hittable for Keyframed {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        let placement = self.placement(r.time);
+        let hit = self.object.hit(&placement.to_local(r), t_range)?;
+        Some(placement.to_world(hit))
+    }
+
+    fn hits(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Vec<Record> {
+        let placement = self.placement(r.time);
+        self.object
+            .hits(&placement.to_local(r), t_range)
+            .into_iter()
+            .map(|hit| placement.to_world(hit))
+            .collect()
+    }
+
+    /// Union of the bounds over the whole motion
+    fn bounding_box(&self) -> Option<Aabb> {
+        let object = self.object.bounding_box()?;
+
+        if self.rotation.times().count() == 1 {
+            // Without rotation the corners move linearly between the times of
+            // scale and translation keys, so bounds at those times cover it
+            return self
+                .scale
+                .times()
+                .chain(self.translation.times())
+                .map(|time| object.transformed(&self.transform(time)))
+                .reduce(|a, b| a.surrounding(&b));
+        }
+
+        // Rotation sweeps the object around the origin, within the largest
+        // scaled distance of any corner from it
+        let reach = object
+            .corners()
+            .iter()
+            .map(|corner| corner.mag())
+            .fold(0.0, f64::max);
+        let largest_scale = self
+            .scale
+            .values()
+            .map(|scale| scale.x.abs().max(scale.y.abs()).max(scale.z.abs()))
+            .fold(0.0, f64::max);
+        let radius = Vec3::ONE * (reach * largest_scale);
+        self.translation
+            .values()
+            .map(|&translation| Aabb::new(translation - radius, translation + radius))
+            .reduce(|a, b| a.surrounding(&b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::{Sphere, Transformed};
+    use crate::material::Lambertian;
+    use crate::vec::{Color, Point};
+
+    fn sphere() -> Sphere {
+        Sphere::new(
+            Point::new(1.0, 0.5, 0.0),
+            0.5,
+            Arc::new(Lambertian::new(Color::ONE)),
+        )
+    }
+
+    fn animated() -> Keyframed {
+        Keyframed::new(Arc::new(sphere()))
+            .with_scale(Keyframes::new(vec![
+                (0.0, Vec3::ONE),
+                (1.0, Vec3::new(2.0, 1.0, 0.5)),
+            ]))
+            .with_rotation(Keyframes::new(vec![
+                (0.0, Quat::IDENTITY),
+                (1.0, Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 90.0)),
+            ]))
+            .with_translation(Keyframes::new(vec![(0.0, Vec3::ZERO), (1.0, Vec3::ONE)]))
+    }
+
+    #[test]
+    fn hits_match_transformed_instance() {
+        let keyframed = animated();
+        let target = Point::new(1.0, 1.0, -0.5);
+        let r = Ray {
+            time: 0.6,
+            ..Ray::new(
+                Point::new(-3.0, 2.0, -4.0),
+                target - Point::new(-3.0, 2.0, -4.0),
+            )
+        };
+        let instance = Transformed::new(Arc::new(sphere()), keyframed.transform(r.time));
+
+        let expected = instance.hit(&r, 0.0..=f64::INFINITY).unwrap();
+        let hit = keyframed.hit(&r, 0.0..=f64::INFINITY).unwrap();
+        assert!((hit.distance - expected.distance).abs() < 1e-9);
+        assert!((hit.point - expected.point).mag() < 1e-9);
+        assert!((hit.normal - expected.normal).mag() < 1e-9);
+    }
+
+    #[test]
+    fn bounds_cover_the_motion() {
+        let keyframed = animated();
+        let bounds = keyframed.bounding_box().unwrap();
+        for step in 0..=20 {
+            let time = step as f64 / 20.0;
+            let moved = sphere()
+                .bounding_box()
+                .unwrap()
+                .transformed(&keyframed.transform(time));
+            for corner in moved.corners().iter() {
+                let inside = |axis: fn(&Point) -> f64| {
+                    axis(&bounds.min) <= axis(corner) && axis(corner) <= axis(&bounds.max)
+                };
+                assert!(inside(|p| p.x) && inside(|p| p.y) && inside(|p| p.z));
+            }
+        }
+    }
+}
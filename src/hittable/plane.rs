@@ -0,0 +1,58 @@
+use super::{Aabb, Hittable, Record};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vec::{Frame, Point, Vec3};
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Infinite flat surface
+pub struct Plane {
+    point: Point,
+    /// Texture axes in the plane, with the unit normal as `n`
+    frame: Frame,
+    material: Arc<dyn Material>,
+}
+
+impl Plane {
+    /// Plane through `point` facing along `normal`. Textures repeat every unit
+    /// of distance along the plane.
+    pub fn new(point: Point, normal: Vec3, material: Arc<dyn Material>) -> Self {
+        Self {
+            point,
+            frame: Frame::new(normal.unit(), Vec3::new(1.0, 0.0, 0.0)),
+            material,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        let denominator = self.frame.n.dot(r.direction);
+        if denominator.abs() < 1.0e-8 {
+            // Parallel to the plane
+            return None;
+        }
+
+        let distance = self.frame.n.dot(self.point - r.origin) / denominator;
+        if !t_range.contains(&distance) {
+            return None;
+        }
+
+        let point = r.at(distance);
+        let local = self.frame.to_local(point - self.point);
+        Some(Record::new(
+            r,
+            point,
+            self.frame.n,
+            distance,
+            (local.x.rem_euclid(1.0), local.y.rem_euclid(1.0)),
+            (self.frame.s, self.frame.t),
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
+}
@@ -0,0 +1,122 @@
+use super::{Aabb, Hittable, Record};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vec::{Point, Vec3};
+
+use std::ops::{Range, RangeInclusive};
+use std::sync::Arc;
+
+/// Parallelogram spanned by two edges from a corner
+pub struct Quad {
+    corner: Point,
+    u: Vec3,
+    v: Vec3,
+    normal: Vec3,
+    /// Scaled normal for finding the planar coordinates of a point
+    w: Vec3,
+    material: Arc<dyn Material>,
+}
+
+impl Quad {
+    /// Quad with corners `corner`, `corner + u`, `corner + v` and
+    /// `corner + u + v`. Texture coordinates run along `u` and `v`, and the
+    /// outward normal is `u × v`.
+    pub fn new(corner: Point, u: Vec3, v: Vec3, material: Arc<dyn Material>) -> Self {
+        let n = u.cross(v);
+        Self {
+            corner,
+            u,
+            v,
+            normal: n.unit(),
+            w: n / n.mag_squared(),
+            material,
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        let denominator = self.normal.dot(r.direction);
+        if denominator.abs() < 1.0e-8 {
+            // Parallel to the plane
+            return None;
+        }
+
+        let distance = self.normal.dot(self.corner - r.origin) / denominator;
+        if !t_range.contains(&distance) {
+            return None;
+        }
+
+        let point = r.at(distance);
+        let planar = point - self.corner;
+        let alpha = self.w.dot(planar.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        Some(Record::new(
+            r,
+            point,
+            self.normal,
+            distance,
+            (alpha, beta),
+            (self.u, self.v),
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(Aabb::from_points(&[
+            self.corner,
+            self.corner + self.u,
+            self.corner + self.v,
+            self.corner + self.u + self.v,
+        ]))
+    }
+}
+
+/// Rectangle aligned with two of the coordinate axes
+pub struct Rect(Quad);
+
+impl Rect {
+    /// Rectangle in the plane `z = k`, facing +Z
+    pub fn xy(x: Range<f64>, y: Range<f64>, k: f64, material: Arc<dyn Material>) -> Self {
+        Self(Quad::new(
+            Point::new(x.start, y.start, k),
+            Vec3::new(x.end - x.start, 0.0, 0.0),
+            Vec3::new(0.0, y.end - y.start, 0.0),
+            material,
+        ))
+    }
+
+    /// Rectangle in the plane `y = k`, facing +Y
+    pub fn xz(x: Range<f64>, z: Range<f64>, k: f64, material: Arc<dyn Material>) -> Self {
+        Self(Quad::new(
+            Point::new(x.start, k, z.start),
+            Vec3::new(0.0, 0.0, z.end - z.start),
+            Vec3::new(x.end - x.start, 0.0, 0.0),
+            material,
+        ))
+    }
+
+    /// Rectangle in the plane `x = k`, facing +X
+    pub fn yz(y: Range<f64>, z: Range<f64>, k: f64, material: Arc<dyn Material>) -> Self {
+        Self(Quad::new(
+            Point::new(k, y.start, z.start),
+            Vec3::new(0.0, y.end - y.start, 0.0),
+            Vec3::new(0.0, 0.0, z.end - z.start),
+            material,
+        ))
+    }
+}
+
+impl Hittable for Rect {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        self.0.hit(r, t_range)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.0.bounding_box()
+    }
+}
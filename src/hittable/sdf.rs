@@ -0,0 +1,244 @@
+use super::{Aabb, Hittable, Record};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vec::{Frame, Point, Vec3};
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Signed distance from a point to the nearest surface: positive outside,
+/// negative inside. Distances may be underestimated but never overestimated,
+/// or sphere tracing can step through the surface.
+pub trait DistanceField: Send + Sync {
+    fn distance(&self, p: Point) -> f64;
+}
+
+impl<F> DistanceField for F
+where
+    F: Fn(Point) -> f64 + Send + Sync,
+{
+    fn distance(&self, p: Point) -> f64 {
+        self(p)
+    }
+}
+
+/// Procedural shape defined by a signed distance field, found by
+/// [sphere tracing](https://graphics.stanford.edu/courses/cs348b-20-spring-content/uploads/hart.pdf)
+pub struct Sdf {
+    field: Arc<dyn DistanceField>,
+    /// Region containing the whole surface, limiting how far rays march
+    bounds: Aabb,
+    material: Arc<dyn Material>,
+}
+
+impl Sdf {
+    /// Distance at which a marching ray is considered to have hit the surface
+    const SURFACE_DISTANCE: f64 = 1.0e-5;
+    /// Give up marching after this many steps, e.g. for rays grazing the
+    /// surface
+    const MAX_STEPS: usize = 512;
+
+    pub fn new(field: Arc<dyn DistanceField>, bounds: Aabb, material: Arc<dyn Material>) -> Self {
+        Self {
+            field,
+            bounds,
+            material,
+        }
+    }
+
+    /// Gradient of the field by central differences on a tetrahedron
+    fn normal(&self, p: Point) -> Vec3 {
+        let h = 1.0e-5;
+        [
+            Vec3::new(1.0, -1.0, -1.0),
+            Vec3::new(-1.0, -1.0, 1.0),
+            Vec3::new(-1.0, 1.0, -1.0),
+            Vec3::new(1.0, 1.0, 1.0),
+        ]
+        .iter()
+        .fold(Vec3::ZERO, |sum, &k| {
+            sum + self.field.distance(p + h * k) * k
+        })
+        .unit()
+    }
+}
+
+impl Hittable for Sdf {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        let (t_enter, t_leave) = self.bounds.clip(r)?;
+        let t_end = t_range.end().min(t_leave);
+        let mut distance = t_range.start().max(t_enter);
+
+        // March along the ray by the distance to the nearest surface, which
+        // can never overshoot it. Rays starting inside march by the absolute
+        // distance to find their way out.
+        let ray_length = r.direction.mag();
+        // Rays spawned on the surface, after reflection or refraction, must
+        // first move away from it or they would hit it again straight away
+        let mut leaving = *t_range.start() >= t_enter
+            && self.field.distance(r.at(distance)).abs() < Self::SURFACE_DISTANCE;
+        for _ in 0..Self::MAX_STEPS {
+            if distance > t_end {
+                return None;
+            }
+            let point = r.at(distance);
+            let to_surface = self.field.distance(point).abs();
+            if to_surface < Self::SURFACE_DISTANCE && leaving {
+                distance += Self::SURFACE_DISTANCE / ray_length;
+                continue;
+            }
+            leaving = false;
+            if to_surface < Self::SURFACE_DISTANCE {
+                let outward_normal = self.normal(point);
+                // There is no natural surface parameterization, so texture
+                // coordinates are left at zero
+                let frame = Frame::new(outward_normal, Vec3::new(1.0, 0.0, 0.0));
+                return Some(Record::new(
+                    r,
+                    point,
+                    outward_normal,
+                    distance,
+                    (0.0, 0.0),
+                    (frame.s, frame.t),
+                    self.material.clone(),
+                ));
+            }
+            distance += to_surface / ray_length;
+        }
+        None
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
+
+pub fn sphere(center: Point, radius: f64) -> impl DistanceField {
+    move |p: Point| (p - center).mag() - radius
+}
+
+/// Box with edges rounded off by `radius`, where `half_size` is measured to
+/// the start of the rounding
+pub fn rounded_box(center: Point, half_size: Vec3, radius: f64) -> impl DistanceField {
+    move |p: Point| {
+        let q = p - center;
+        let q = Vec3::new(
+            q.x.abs() - half_size.x,
+            q.y.abs() - half_size.y,
+            q.z.abs() - half_size.z,
+        );
+        let outside = Vec3::new(q.x.max(0.0), q.y.max(0.0), q.z.max(0.0)).mag();
+        let inside = q.x.max(q.y).max(q.z).min(0.0);
+        outside + inside - radius
+    }
+}
+
+/// Ring lying flat in the XZ plane
+pub fn torus(center: Point, major_radius: f64, minor_radius: f64) -> impl DistanceField {
+    move |p: Point| {
+        let q = p - center;
+        let ring = (q.x.powi(2) + q.z.powi(2)).sqrt() - major_radius;
+        (ring.powi(2) + q.y.powi(2)).sqrt() - minor_radius
+    }
+}
+
+/// Cylinder between two points with hemispherical ends
+pub fn capsule(a: Point, b: Point, radius: f64) -> impl DistanceField {
+    move |p: Point| {
+        let pa = p - a;
+        let ba = b - a;
+        let h = (pa.dot(ba) / ba.mag_squared()).clamp(0.0, 1.0);
+        (pa - h * ba).mag() - radius
+    }
+}
+
+/// [Mandelbulb](https://en.wikipedia.org/wiki/Mandelbulb) fractal of the
+/// given power, about `scale` in radius. The classic bulb has power 8.
+pub fn mandelbulb(center: Point, scale: f64, power: f64, iterations: usize) -> impl DistanceField {
+    move |p: Point| {
+        let c = (p - center) / scale;
+        let mut z = c;
+        let mut dr = 1.0;
+        let mut r = z.mag();
+        for _ in 0..iterations {
+            if r > 2.0 {
+                break;
+            }
+            // Raise z to the power in spherical coordinates
+            let theta = (z.z / r).acos() * power;
+            let phi = z.y.atan2(z.x) * power;
+            dr = r.powf(power - 1.0) * power * dr + 1.0;
+            z = r.powf(power)
+                * Vec3::new(
+                    theta.sin() * phi.cos(),
+                    theta.sin() * phi.sin(),
+                    theta.cos(),
+                )
+                + c;
+            r = z.mag();
+        }
+        0.5 * r.ln() * r / dr * scale
+    }
+}
+
+/// Union of two shapes blended together over a distance of about `k`
+pub fn smooth_union(
+    a: impl DistanceField + 'static,
+    b: impl DistanceField + 'static,
+    k: f64,
+) -> impl DistanceField {
+    move |p: Point| {
+        let (da, db) = (a.distance(p), b.distance(p));
+        let h = (0.5 + 0.5 * (db - da) / k).clamp(0.0, 1.0);
+        db + (da - db) * h - k * h * (1.0 - h)
+    }
+}
+
+/// Shape `a` with `b` carved out of it, blending the edges over a distance of
+/// about `k`
+pub fn smooth_subtraction(
+    a: impl DistanceField + 'static,
+    b: impl DistanceField + 'static,
+    k: f64,
+) -> impl DistanceField {
+    move |p: Point| {
+        let (da, db) = (a.distance(p), b.distance(p));
+        let h = (0.5 - 0.5 * (da + db) / k).clamp(0.0, 1.0);
+        da + (-db - da) * h + k * h * (1.0 - h)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec::Color;
+
+    fn unit_sphere() -> Sdf {
+        Sdf::new(
+            Arc::new(sphere(Point::ZERO, 1.0)),
+            Aabb::new(-Vec3::ONE, Vec3::ONE),
+            Arc::new(Lambertian::new(Color::ONE)),
+        )
+    }
+
+    #[test]
+    fn rays_leaving_the_surface_miss_it() {
+        let r = Ray::new(Point::new(1.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 0.0));
+        assert!(unit_sphere().hit(&r, 0.0..=f64::INFINITY).is_none());
+    }
+
+    #[test]
+    fn rays_entering_from_the_surface_hit_the_far_side() {
+        let sdf = unit_sphere();
+        let r = Ray::new(Point::new(-2.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let entry = sdf.hit(&r, 0.0..=f64::INFINITY).unwrap();
+        assert!((entry.distance - 1.0).abs() < 1e-4);
+
+        // Refracted straight on from where the ray entered
+        let inside = r.spawn(entry.point, r.direction);
+        let exit = sdf.hit(&inside, 0.0..=f64::INFINITY).unwrap();
+        assert!((exit.distance - 2.0).abs() < 1e-4);
+        assert!(!exit.front_face);
+    }
+}
@@ -1,8 +1,9 @@
-use super::{Hittable, Record};
+use super::{Aabb, Hittable, Record};
 use crate::material::Material;
 use crate::ray::Ray;
-use crate::vec::Point;
+use crate::vec::{Point, Vec3};
 
+use std::f64::consts::PI;
 use std::ops::RangeInclusive;
 use std::sync::Arc;
 
@@ -20,11 +21,37 @@ impl Sphere {
             material: material.clone(),
         }
     }
-}
 
-impl Hittable for Sphere {
-    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
-        let oc = r.origin - self.center;
+    /// Spherical texture coordinates of a point on the unit sphere.
+    ///
+    /// `u` is the angle around the Y axis starting from X=-1, and `v` is the
+    /// angle from Y=-1 up to Y=+1.
+    fn uv(p: &Point) -> (f64, f64) {
+        let theta = (-p.y).acos();
+        let phi = (-p.z).atan2(p.x) + PI;
+        (phi / (2.0 * PI), theta / PI)
+    }
+
+    /// Surface derivatives `(dpdu, dpdv)` at a point on the unit sphere
+    fn tangents(&self, p: &Point) -> (Vec3, Vec3) {
+        let sin_theta = (p.x.powi(2) + p.z.powi(2)).sqrt();
+        if sin_theta < 1.0e-8 {
+            // At the poles the parameterization degenerates, so pick any
+            // basis perpendicular to the normal
+            let dpdu = 2.0 * PI * self.radius * Vec3::new(1.0, 0.0, 0.0);
+            let dpdv = PI * self.radius * Vec3::new(0.0, 0.0, p.y.signum());
+            return (dpdu, dpdv);
+        }
+        let dpdu = 2.0 * PI * self.radius * Vec3::new(p.z, 0.0, -p.x);
+        let dpdv =
+            PI * self.radius * Vec3::new(-p.x * p.y / sin_theta, sin_theta, -p.y * p.z / sin_theta);
+        (dpdu, dpdv)
+    }
+
+    /// Distances to the sphere as if it were centered at `center`, nearest
+    /// first. A tangent ray has two equal roots.
+    fn roots(&self, center: Point, r: &Ray) -> Option<[f64; 2]> {
+        let oc = r.origin - center;
         let a = r.direction.mag_squared();
         let half_b = oc.dot(r.direction);
         let c = oc.mag_squared() - self.radius.powi(2);
@@ -35,25 +62,135 @@ impl Hittable for Sphere {
         }
 
         let sqrtd = discriminant.sqrt();
+        Some([(-half_b - sqrtd) / a, (-half_b + sqrtd) / a])
+    }
 
+    /// Intersect the sphere as if it were centered at `center`
+    fn hit_centered(&self, center: Point, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
         // Find the nearest root within range
-        let mut root = (-half_b - sqrtd) / a;
-        if !t_range.contains(&root) {
-            root = (-half_b + sqrtd) / a;
-            if !t_range.contains(&root) {
-                return None;
-            }
-        }
+        let root = self
+            .roots(center, r)?
+            .iter()
+            .copied()
+            .find(|t| t_range.contains(t))?;
+        Some(self.record(center, r, root))
+    }
 
+    /// Every crossing of the sphere centered at `center`, including both for
+    /// a tangent ray
+    fn hits_centered(&self, center: Point, r: &Ray, t_range: RangeInclusive<f64>) -> Vec<Record> {
+        self.roots(center, r)
+            .iter()
+            .flatten()
+            .filter(|t| t_range.contains(t))
+            .map(|&root| self.record(center, r, root))
+            .collect()
+    }
+
+    fn record(&self, center: Point, r: &Ray, root: f64) -> Record {
         let point = r.at(root);
-        let outward_normal = (point - self.center) / self.radius;
+        let outward_normal = (point - center) / self.radius;
 
-        Some(Record::new(
+        Record::new(
             r,
             point,
             outward_normal,
             root,
+            Self::uv(&outward_normal),
+            self.tangents(&outward_normal),
             self.material.clone(),
-        ))
+        )
+    }
+}
+
+impl Hittable for Sphere {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        self.hit_centered(self.center, r, t_range)
+    }
+
+    fn hits(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Vec<Record> {
+        self.hits_centered(self.center, r, t_range)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vec3::new(self.radius, self.radius, self.radius);
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+}
+
+/// Sphere whose center moves in a straight line at constant speed
+pub struct MovingSphere {
+    /// Shape and material, centered where the sphere is at `time0`
+    sphere: Sphere,
+    center1: Point,
+    time0: f64,
+    time1: f64,
+}
+
+impl MovingSphere {
+    /// Sphere at `center0` at time `time0` and `center1` at time `time1`. If
+    /// the times are equal the sphere stays at `center0`.
+    pub fn new(
+        (center0, time0): (Point, f64),
+        (center1, time1): (Point, f64),
+        radius: f64,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            sphere: Sphere::new(center0, radius, material),
+            center1,
+            time0,
+            time1,
+        }
+    }
+
+    fn center(&self, time: f64) -> Point {
+        let center0 = self.sphere.center;
+        // A sphere given no time to move stays put
+        if self.time1 == self.time0 {
+            return center0;
+        }
+        let fraction = (time - self.time0) / (self.time1 - self.time0);
+        center0 + fraction * (self.center1 - center0)
+    }
+}
+
+impl Hittable for MovingSphere {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        self.sphere.hit_centered(self.center(r.time), r, t_range)
+    }
+
+    fn hits(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Vec<Record> {
+        self.sphere.hits_centered(self.center(r.time), r, t_range)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let start = self.sphere.bounding_box()?;
+        let offset = self.center1 - self.sphere.center;
+        Some(start.surrounding(&Aabb::new(start.min + offset, start.max + offset)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec::Color;
+
+    #[test]
+    fn moving_sphere_without_time_to_move_stays_put() {
+        let center = Point::new(0.0, 0.0, -2.0);
+        let sphere = MovingSphere::new(
+            (center, 0.5),
+            (Point::new(3.0, 0.0, -2.0), 0.5),
+            1.0,
+            Arc::new(Lambertian::new(Color::ONE)),
+        );
+        let r = Ray {
+            time: 0.5,
+            ..Ray::new(Point::ZERO, Vec3::new(0.0, 0.0, -1.0))
+        };
+        let hit = sphere.hit(&r, 0.0..=f64::INFINITY).unwrap();
+        assert!((hit.distance - 1.0).abs() < 1e-12);
     }
 }
@@ -0,0 +1,309 @@
+use super::{Aabb, Hittable, Record};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vec::{Point, Vec3};
+
+use std::f64::consts::PI;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Ring-shaped surface lying flat in the XZ plane around the Y axis
+pub struct Torus {
+    center: Point,
+    /// Distance from the center to the middle of the tube
+    major_radius: f64,
+    /// Radius of the tube
+    minor_radius: f64,
+    material: Arc<dyn Material>,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point,
+        major_radius: f64,
+        minor_radius: f64,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            center,
+            major_radius,
+            minor_radius,
+            material,
+        }
+    }
+}
+
+impl Torus {
+    /// Distances to the surface within the range, nearest first. Tangent rays
+    /// have pairs of equal roots.
+    fn distances(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Vec<f64> {
+        // Start from where the ray enters the bounding box, so the quartic's
+        // coefficients stay small and its roots precise
+        let (t_enter, t_leave) = match self.bounding_box().and_then(|bounds| bounds.clip(r)) {
+            Some(clipped) => clipped,
+            None => return Vec::new(),
+        };
+        let t_start = t_range.start().max(t_enter);
+        let t_end = t_range.end().min(t_leave);
+        if t_start > t_end {
+            return Vec::new();
+        }
+        let shift = t_start.max(0.0);
+
+        // Substitute the ray into (|p|² + R² - r²)² = 4R²(x² + z²)
+        let o = r.origin + shift * r.direction - self.center;
+        let d = r.direction;
+        let r2 = self.major_radius.powi(2);
+        let a = d.mag_squared();
+        let f = o.dot(d);
+        let e = o.mag_squared() + r2 - self.minor_radius.powi(2);
+        let coefficients = [
+            a.powi(2),
+            4.0 * a * f,
+            4.0 * f.powi(2) + 2.0 * a * e - 4.0 * r2 * (d.x.powi(2) + d.z.powi(2)),
+            4.0 * e * f - 8.0 * r2 * (o.x * d.x + o.z * d.z),
+            e.powi(2) - 4.0 * r2 * (o.x.powi(2) + o.z.powi(2)),
+        ];
+
+        let mut distances: Vec<f64> = quartic_roots(coefficients)
+            .into_iter()
+            .map(|t| t + shift)
+            // The box touches the surface, so roots there may round outside it
+            .filter(|t| t_range.contains(t))
+            .collect();
+        distances.sort_by(f64::total_cmp);
+        distances
+    }
+
+    fn record(&self, r: &Ray, distance: f64) -> Record {
+        let point = r.at(distance);
+        let p = point - self.center;
+        let rho = (p.x.powi(2) + p.z.powi(2)).sqrt();
+        let radial = if rho > 0.0 {
+            Vec3::new(p.x, 0.0, p.z) / rho
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let outward_normal = (p - self.major_radius * radial).unit();
+
+        // `u` wraps around the Y axis like a sphere's, and `v` around the
+        // tube starting from its outer equator
+        let theta = p.y.atan2(rho - self.major_radius).rem_euclid(2.0 * PI);
+        let u = ((-p.z).atan2(p.x) + PI) / (2.0 * PI);
+        let v = theta / (2.0 * PI);
+        let dpdu = 2.0 * PI * Vec3::new(p.z, 0.0, -p.x);
+        let dpdv = 2.0
+            * PI
+            * self.minor_radius
+            * (theta.cos() * Vec3::new(0.0, 1.0, 0.0) - theta.sin() * radial);
+
+        Record::new(
+            r,
+            point,
+            outward_normal,
+            distance,
+            (u, v),
+            (dpdu, dpdv),
+            self.material.clone(),
+        )
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        let distance = *self.distances(r, t_range).first()?;
+        Some(self.record(r, distance))
+    }
+
+    fn hits(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Vec<Record> {
+        self.distances(r, t_range)
+            .into_iter()
+            .map(|distance| self.record(r, distance))
+            .collect()
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let outer = self.major_radius + self.minor_radius;
+        let extent = Vec3::new(outer, self.minor_radius, outer);
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+}
+
+/// Real roots of `c[0] x⁴ + c[1] x³ + c[2] x² + c[3] x + c[4]` by
+/// [Ferrari's method](https://en.wikipedia.org/wiki/Quartic_equation#Ferrari's_solution),
+/// polished with Newton's method
+fn quartic_roots(c: [f64; 5]) -> Vec<f64> {
+    let (a, b, cc, d) = (c[1] / c[0], c[2] / c[0], c[3] / c[0], c[4] / c[0]);
+
+    // Depressed quartic y⁴ + p y² + q y + r with x = y - a/4
+    let p = b - 3.0 * a.powi(2) / 8.0;
+    let q = cc - a * b / 2.0 + a.powi(3) / 8.0;
+    let r = d - a * cc / 4.0 + a.powi(2) * b / 16.0 - 3.0 * a.powi(4) / 256.0;
+
+    let mut roots = Vec::with_capacity(4);
+    let mut push_quadratic = |b: f64, c: f64| {
+        let discriminant = b.powi(2) - 4.0 * c;
+        if discriminant >= 0.0 {
+            let sqrtd = discriminant.sqrt();
+            roots.push((-b - sqrtd) / 2.0);
+            roots.push((-b + sqrtd) / 2.0);
+        }
+    };
+
+    if q.abs() < 1.0e-12 {
+        // Biquadratic in y²
+        let discriminant = p.powi(2) - 4.0 * r;
+        if discriminant >= 0.0 {
+            let sqrtd = discriminant.sqrt();
+            for &z in &[(-p - sqrtd) / 2.0, (-p + sqrtd) / 2.0] {
+                if z >= 0.0 {
+                    roots.push(-z.sqrt());
+                    roots.push(z.sqrt());
+                }
+            }
+        }
+    } else {
+        // Any positive root of the resolvent cubic splits the quartic into
+        // two quadratics
+        let m = largest_cubic_root(p, p.powi(2) / 4.0 - r, -q.powi(2) / 8.0).max(0.0);
+        let s = (2.0 * m).sqrt();
+        // The quadratics' constant terms are p/2 + m ± q/2s. Nearly symmetric
+        // quartics, like rays almost parallel to the axis through the tube,
+        // have both q and s tiny and their ratio imprecise, so take its size
+        // from the terms multiplying to r instead.
+        let half_difference = if s > 1.0e-4 {
+            q / (2.0 * s)
+        } else {
+            ((p / 2.0 + m).powi(2) - r).max(0.0).sqrt().copysign(q)
+        };
+        push_quadratic(-s, p / 2.0 + m + half_difference);
+        push_quadratic(s, p / 2.0 + m - half_difference);
+    }
+
+    let evaluate = |x: f64| {
+        let value = (((c[0] * x + c[1]) * x + c[2]) * x + c[3]) * x + c[4];
+        let slope = ((4.0 * c[0] * x + 3.0 * c[1]) * x + 2.0 * c[2]) * x + c[3];
+        (value, slope)
+    };
+    roots
+        .into_iter()
+        .map(|y| {
+            let mut x = y - a / 4.0;
+            for _ in 0..2 {
+                let (value, slope) = evaluate(x);
+                if slope != 0.0 {
+                    x -= value / slope;
+                }
+            }
+            x
+        })
+        .collect()
+}
+
+/// Largest real root of `x³ + a x² + b x + c`
+fn largest_cubic_root(a: f64, b: f64, c: f64) -> f64 {
+    // Depressed cubic t³ + p t + q with x = t - a/3
+    let p = b - a.powi(2) / 3.0;
+    let q = 2.0 * a.powi(3) / 27.0 - a * b / 3.0 + c;
+    let discriminant = (q / 2.0).powi(2) + (p / 3.0).powi(3);
+
+    let t = if discriminant >= 0.0 {
+        let sqrtd = discriminant.sqrt();
+        (-q / 2.0 + sqrtd).cbrt() + (-q / 2.0 - sqrtd).cbrt()
+    } else {
+        // Three real roots, of which the first trigonometric one is largest
+        let phi = ((3.0 * q / (2.0 * p)) * (-3.0 / p).sqrt())
+            .clamp(-1.0, 1.0)
+            .acos();
+        2.0 * (-p / 3.0).sqrt() * (phi / 3.0).cos()
+    };
+    t - a / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec::Color;
+    use rand::distributions::{Distribution, Uniform};
+
+    fn torus() -> Torus {
+        Torus::new(Point::ZERO, 2.0, 0.5, Arc::new(Lambertian::new(Color::ONE)))
+    }
+
+    fn distances(origin: Point, direction: Vec3) -> Vec<f64> {
+        torus().distances(&Ray::new(origin, direction), 0.0..=f64::INFINITY)
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-6, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn axial_ray_passes_through_the_hole() {
+        let hits = distances(Point::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        assert!(hits.is_empty(), "{:?}", hits);
+    }
+
+    #[test]
+    fn ray_through_the_hole_crosses_the_tube_twice() {
+        let hits = distances(Point::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert_close(&hits, &[2.5, 3.5, 6.5, 7.5]);
+
+        // The outermost hits are where the ray enters and leaves the bounding
+        // box, and must survive rounding
+        let hits = distances(
+            Point::new(-5.0, 2.215959740791701e-8, 2.0292390229062206e-8),
+            Vec3::new(1.0, -2.163139924860557e-9, -1.2897320262962079e-8),
+        );
+        assert_close(&hits, &[2.5, 3.5, 6.5, 7.5]);
+    }
+
+    #[test]
+    fn nearly_axial_ray_through_the_tube_hits_it() {
+        // Almost symmetric about the middle of the tube, so the resolvent
+        // cubic's largest root is nearly zero
+        let hits = distances(
+            Point::new(1.9999999987007964, 5.0, 3.2912112322860867e-9),
+            Vec3::new(3.5410157028792355e-9, -1.0, -2.037733507873819e-9),
+        );
+        assert_close(&hits, &[4.5, 5.5]);
+    }
+
+    #[test]
+    fn grazing_ray_touches_the_top() {
+        let hits = distances(Point::new(-5.0, 0.5, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert_close(&hits, &[3.0, 3.0, 7.0, 7.0]);
+    }
+
+    #[test]
+    fn hits_lie_on_the_surface() {
+        let torus = torus();
+        let mut rng = rand::thread_rng();
+        let coordinate = Uniform::new_inclusive(-3.0, 3.0);
+        let mut hits = 0;
+        while hits < 1000 {
+            let origin = Point::new(
+                coordinate.sample(&mut rng),
+                coordinate.sample(&mut rng),
+                coordinate.sample(&mut rng),
+            );
+            let target = Point::new(
+                coordinate.sample(&mut rng),
+                coordinate.sample(&mut rng) / 4.0,
+                coordinate.sample(&mut rng),
+            );
+            let r = Ray::new(origin, target - origin);
+            for distance in torus.distances(&r, 0.0..=f64::INFINITY) {
+                let p = r.at(distance);
+                let implicit =
+                    (p.mag_squared() + 4.0 - 0.25).powi(2) - 16.0 * (p.x.powi(2) + p.z.powi(2));
+                assert!(implicit.abs() < 1e-6, "{:?}: {}", p.components(), implicit);
+                hits += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use super::{Aabb, Hittable, Record};
+use crate::ray::Ray;
+use crate::vec::Mat4;
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Instance of a shared object placed in the world by an affine transform
+pub struct Transformed {
+    object: Arc<dyn Hittable>,
+    /// Object space to world space
+    transform: Mat4,
+    /// World space to object space
+    inverse: Mat4,
+    /// Inverse transpose, which keeps normals perpendicular to the surface
+    normal_transform: Mat4,
+}
+
+impl Transformed {
+    /// Panics if the transform is singular, e.g. a zero scale factor
+    pub fn new(object: Arc<dyn Hittable>, transform: Mat4) -> Self {
+        let inverse = transform
+            .inverse()
+            .expect("instance transform must be invertible");
+        Self {
+            object,
+            transform,
+            inverse,
+            normal_transform: inverse.transpose(),
+        }
+    }
+
+    fn to_local(&self, r: &Ray) -> Ray {
+        // The direction is not normalized, so distances along the object
+        // space ray match those along the world space ray
+        r.spawn(
+            self.inverse.transform_point(r.origin),
+            self.inverse.transform_vector(r.direction),
+        )
+    }
+
+    fn to_world(&self, mut hit: Record) -> Record {
+        hit.point = self.transform.transform_point(hit.point);
+        hit.normal = self.normal_transform.transform_vector(hit.normal).unit();
+        hit.dpdu = self.transform.transform_vector(hit.dpdu);
+        hit.dpdv = self.transform.transform_vector(hit.dpdv);
+        hit
+    }
+}
+
+impl Hittable for Transformed {
+    fn hit(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Option<Record> {
+        let hit = self.object.hit(&self.to_local(r), t_range)?;
+        Some(self.to_world(hit))
+    }
+
+    fn hits(&self, r: &Ray, t_range: RangeInclusive<f64>) -> Vec<Record> {
+        self.object
+            .hits(&self.to_local(r), t_range)
+            .into_iter()
+            .map(|hit| self.to_world(hit))
+            .collect()
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.object.bounding_box()?.transformed(&self.transform))
+    }
+}
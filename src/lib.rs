@@ -0,0 +1,8 @@
+pub mod animation;
+pub mod camera;
+pub mod hittable;
+pub mod material;
+pub mod ray;
+pub mod spectrum;
+pub mod texture;
+pub mod vec;
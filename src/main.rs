@@ -6,17 +6,16 @@ use std::io::{BufWriter, Write};
 use std::path::Path;
 use std::sync::Arc;
 
-mod camera;
-mod hittable;
-mod material;
-mod ray;
-mod vec;
-
-use camera::Camera;
-use hittable::{Hittable, Sphere};
-use material::{Dielectric, Lambertian, Metal, RefractiveIndex, Scatter};
-use ray::Ray;
-use vec::{Color, Point, Vec3};
+use raytrace::animation::Keyframes;
+use raytrace::camera::{
+    Camera, Equirectangular, Fisheye, FisheyeProjection, Orthographic, Perspective, Stereo,
+    StereoLayout,
+};
+use raytrace::hittable::{self, Hittable, Keyframed, Sphere};
+use raytrace::material::{Dielectric, Lambertian, Metal, RefractiveIndex, Scatter};
+use raytrace::ray::Ray;
+use raytrace::spectrum::{SampledSpectrum, SampledWavelengths};
+use raytrace::vec::{Color, Mat4, Point, Vec3};
 
 fn ray_color(
     r: &Ray,
@@ -30,24 +29,105 @@ fn ray_color(
     }
 
     if let Some(hit) = world.hit(r, 0.001..=f64::MAX) {
+        // Light is absorbed on its way from the hit by the medium in between
+        let transmittance = r.media.transmittance(hit.distance * r.direction.mag());
+        let emitted = hit.material.emitted(&hit);
         if let Some(Scatter { ray, attenuation }) = hit.material.scatter(r, &hit, vec_dist, rng) {
-            return attenuation.schur(ray_color(
-                &ray,
-                world,
-                depth.saturating_sub(1),
-                vec_dist,
-                rng,
-            ));
+            return transmittance.schur(
+                emitted
+                    + attenuation.schur(ray_color(
+                        &ray,
+                        world,
+                        depth.saturating_sub(1),
+                        vec_dist,
+                        rng,
+                    )),
+            );
         }
-        return Color::ZERO;
+        return transmittance.schur(emitted);
+    }
+
+    sky_color(r)
+}
+
+/// Trace a ray carrying sampled wavelengths, treating RGB colors as spectra
+fn ray_color_spectral(
+    r: &Ray,
+    wavelengths: &SampledWavelengths,
+    world: &hittable::List,
+    depth: usize,
+    vec_dist: &Uniform<f64>,
+    rng: &mut ThreadRng,
+) -> SampledSpectrum {
+    if depth == 0 {
+        return SampledSpectrum::ZERO;
     }
 
+    if let Some(hit) = world.hit(r, 0.001..=f64::MAX) {
+        let transmittance = SampledSpectrum::from_rgb(
+            r.media.transmittance(hit.distance * r.direction.mag()),
+            wavelengths,
+        );
+        let emitted = SampledSpectrum::from_rgb(hit.material.emitted(&hit), wavelengths);
+        if let Some(Scatter { ray, attenuation }) = hit.material.scatter(r, &hit, vec_dist, rng) {
+            let mut attenuation = SampledSpectrum::from_rgb(attenuation, wavelengths);
+            let terminated = |r: &Ray| r.wavelengths.is_some_and(|w| w.is_secondary_terminated());
+            if terminated(&ray) && !terminated(r) {
+                attenuation = attenuation.terminate_secondary();
+            }
+            return transmittance
+                * (emitted
+                    + attenuation
+                        * ray_color_spectral(
+                            &ray,
+                            wavelengths,
+                            world,
+                            depth.saturating_sub(1),
+                            vec_dist,
+                            rng,
+                        ));
+        }
+        return transmittance * emitted;
+    }
+
+    SampledSpectrum::from_rgb(sky_color(r), wavelengths)
+}
+
+fn sky_color(r: &Ray) -> Color {
     let unit_direction = r.direction.unit();
     let t = 0.5 * (unit_direction.y + 1.0);
     (1.0 - t) * Point::new(1.0, 1.0, 1.0) + t * Point::new(0.5, 0.7, 1.0)
 }
 
-fn random_scene(sample_dist: &Uniform<f64>, rng: &mut ThreadRng) -> hittable::List {
+/// Objects to render, and the camera's path through the animation, which runs
+/// from time 0.0 to 1.0
+struct Scene {
+    world: hittable::List,
+    orbit: Orbit,
+}
+
+/// Camera path circling a point
+struct Orbit {
+    look_at: Point,
+    /// Position at no rotation or height
+    start: Point,
+    /// Degrees around the vertical through `look_at`
+    angle: Keyframes<f64>,
+    /// Distance above `start`
+    height: Keyframes<f64>,
+}
+
+impl Orbit {
+    fn look_from(&self, time: f64) -> Point {
+        let rotation = Mat4::rotate(Vec3::new(0.0, 1.0, 0.0), self.angle.at(time));
+        let around = Mat4::translate(-self.look_at)
+            .then(rotation)
+            .then(Mat4::translate(self.look_at));
+        around.transform_point(self.start) + Vec3::new(0.0, self.height.at(time), 0.0)
+    }
+}
+
+fn random_scene(sample_dist: &Uniform<f64>, rng: &mut ThreadRng) -> Scene {
     let mut world = hittable::List::default();
     let ground = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
     world.add(Sphere::new(Point::new(0.0, -1000.0, 0.0), 1000.0, ground));
@@ -95,20 +175,144 @@ fn random_scene(sample_dist: &Uniform<f64>, rng: &mut ThreadRng) -> hittable::Li
     let lambert = Arc::new(Lambertian::new(Color::new(0.4, 0.2, 0.1)));
     world.add(Sphere::new(Point::new(-4.0, 1.0, 0.0), 1.0, lambert));
 
+    // Bounces once over the course of an animation
     let metal = Arc::new(Metal::new(Color::new(0.7, 0.6, 0.3), 0.0));
-    world.add(Sphere::new(Point::new(4.0, 1.0, 0.0), 1.0, metal));
+    let bounce = Keyframes::new(vec![
+        (0.0, Vec3::ZERO),
+        (0.5, Vec3::new(0.0, 1.5, 0.0)),
+        (1.0, Vec3::ZERO),
+    ]);
+    world.add(
+        Keyframed::new(Arc::new(Sphere::new(Point::new(4.0, 1.0, 0.0), 1.0, metal)))
+            .with_translation(bounce),
+    );
+
+    // Circle once around the scene, rising halfway through
+    let orbit = Orbit {
+        look_at: Point::new(0.0, 0.0, 0.0),
+        start: Point::new(13.0, 0.0, 3.0),
+        angle: Keyframes::new(vec![(0.0, 0.0), (1.0, 360.0)]),
+        height: Keyframes::new(vec![(0.0, 2.0), (0.5, 4.0), (1.0, 2.0)]),
+    };
 
-    world
+    Scene { world, orbit }
 }
 
-fn main() {
-    let height: usize = 288;
-    // TODO: A type for this
-    let aspect_ratio = (16, 9);
-    let get_width = |height: usize| aspect_ratio.0 * height / aspect_ratio.1;
-    let width = get_width(height);
-    let samples_per_pixel = 100;
-    let max_depth = 50;
+/// Image size and quality
+struct Settings {
+    width: usize,
+    height: usize,
+    samples_per_pixel: usize,
+    max_depth: usize,
+    spectral: bool,
+}
+
+/// Kinds of camera selectable with `--camera`
+#[derive(Debug, Clone, Copy)]
+enum Projection {
+    Perspective,
+    Orthographic,
+    /// 360° equirectangular panorama
+    Panorama,
+    Fisheye,
+    /// Side-by-side views for the left and right eyes
+    Stereo,
+}
+
+impl Projection {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "perspective" => Some(Projection::Perspective),
+            "orthographic" => Some(Projection::Orthographic),
+            "panorama" => Some(Projection::Panorama),
+            "fisheye" => Some(Projection::Fisheye),
+            "stereo" => Some(Projection::Stereo),
+            _ => None,
+        }
+    }
+
+    /// Image width for a height, given the aspect ratio of a single view
+    fn width(&self, height: usize, aspect_ratio: (usize, usize)) -> usize {
+        let view_width = aspect_ratio.0 * height / aspect_ratio.1;
+        match self {
+            Projection::Panorama => 2 * height,
+            Projection::Stereo => 2 * view_width,
+            _ => view_width,
+        }
+    }
+}
+
+/// Camera on the orbit with its shutter open over an interval of the animation
+fn camera_at(
+    projection: Projection,
+    orbit: &Orbit,
+    (open, close): (f64, f64),
+    aspect_ratio: f64,
+) -> Box<dyn Camera> {
+    let look_from = orbit.look_from(open);
+    let look_at = orbit.look_at;
+    let up_vector = Vec3::new(0.0, 1.0, 0.0);
+    let focal_distance = 10.0;
+    let aperture = 0.1;
+    let vertical_fov = 20.0;
+
+    let perspective = |look_from: Point| {
+        Perspective::new(
+            look_from,
+            look_at,
+            up_vector,
+            vertical_fov,
+            aspect_ratio,
+            aperture,
+            focal_distance,
+        )
+    };
+
+    match projection {
+        Projection::Perspective => Box::new(perspective(look_from).with_shutter(open, close)),
+        Projection::Orthographic => Box::new(
+            Orthographic::new(look_from, look_at, up_vector, 5.0, aspect_ratio)
+                .with_shutter(open, close),
+        ),
+        Projection::Panorama => {
+            Box::new(Equirectangular::new(look_from, look_at, up_vector).with_shutter(open, close))
+        }
+        Projection::Fisheye => Box::new(
+            Fisheye::new(
+                look_from,
+                look_at,
+                up_vector,
+                180.0,
+                aspect_ratio,
+                FisheyeProjection::Equisolid,
+            )
+            .with_shutter(open, close),
+        ),
+        Projection::Stereo => {
+            // Eyes apart at the scene's scale, along the camera's right
+            let right = up_vector.cross(look_from - look_at).unit();
+            let eye_offset = 0.1 * right;
+            Box::new(
+                Stereo::new(
+                    Arc::new(perspective(look_from - eye_offset)),
+                    Arc::new(perspective(look_from + eye_offset)),
+                    StereoLayout::SideBySide,
+                )
+                .with_shutter(open, close),
+            )
+        }
+    }
+}
+
+/// Trace the image, returning RGB bytes row by row from the top
+fn render(camera: &dyn Camera, world: &hittable::List, settings: &Settings) -> Vec<u8> {
+    let Settings {
+        width,
+        height,
+        samples_per_pixel,
+        max_depth,
+        spectral,
+    } = *settings;
 
     // Random number utilities
     let sample_dist = Uniform::new(0.0, 1.0);
@@ -120,27 +324,6 @@ fn main() {
     // Get index of Red value. Green and Blue are +1, +2
     let image_index = |x: usize, y: usize| 3 * (y * width + x);
 
-    let world = random_scene(&sample_dist, &mut rng);
-
-    // Create camera
-    let look_from = Point::new(13.0, 2.0, 3.0);
-    let look_at = Point::new(0.0, 0.0, 0.0);
-    let up_vector = Vec3::new(0.0, 1.0, 0.0);
-    let focal_distance = 10.0;
-    let aperture = 0.1;
-    let vertical_fov = 20.0;
-    let aspect_ratio = aspect_ratio.0 as f64 / aspect_ratio.1 as f64;
-
-    let camera = Camera::new(
-        look_from,
-        look_at,
-        up_vector,
-        vertical_fov,
-        aspect_ratio,
-        aperture,
-        focal_distance,
-    );
-
     for y in 0..height {
         print!("\rScanlines remaining {:>5}", height - y);
         std::io::stdout().flush().unwrap();
@@ -153,7 +336,18 @@ fn main() {
                 let v = 1.0 - (y as f64 + rand_y) / (height - 1) as f64;
 
                 let r = camera.get_ray(u, v, &vec_dist, &mut rng);
-                color += ray_color(&r, &world, max_depth, &vec_dist, &mut rng);
+                color += if spectral {
+                    let wavelengths =
+                        SampledWavelengths::sample_visible(sample_dist.sample(&mut rng));
+                    let r = Ray {
+                        wavelengths: Some(wavelengths),
+                        ..r
+                    };
+                    ray_color_spectral(&r, &wavelengths, world, max_depth, &vec_dist, &mut rng)
+                        .to_rgb(&wavelengths)
+                } else {
+                    ray_color(&r, world, max_depth, &vec_dist, &mut rng)
+                };
             }
             color /= samples_per_pixel as f64;
             // Gamma correction for gamma=2.0 is square root
@@ -168,7 +362,10 @@ fn main() {
     }
     print!("\r");
 
-    let path = Path::new(r"./output/random-scene.png");
+    image_data
+}
+
+fn write_png(path: &Path, width: usize, height: usize, image_data: &[u8]) {
     let file = File::create(path).unwrap();
     let w = &mut BufWriter::new(file);
 
@@ -177,12 +374,113 @@ fn main() {
     encoder.set_depth(png::BitDepth::Eight);
     let mut writer = encoder.write_header().unwrap();
 
-    match writer.write_image_data(&image_data) {
+    match writer.write_image_data(image_data) {
         Ok(()) => {
-            println!("Wrote image data. {} x {}", width, height);
+            println!(
+                "Wrote image data to {}. {} x {}",
+                path.display(),
+                width,
+                height
+            );
         }
         Err(err) => {
             println!("Image writer error: {:?}", err);
         }
     }
 }
+
+const USAGE: &str = "usage: raytrace [--spectral] [--frames N] \
+    [--camera perspective|orthographic|panorama|fisheye|stereo]";
+
+/// Report a bad command line and exit
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    std::process::exit(2);
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let height: usize = 288;
+    // TODO: A type for this
+    let aspect_ratio = (16, 9);
+    let projection =
+        args.iter()
+            .position(|arg| arg == "--camera")
+            .map_or(Projection::Perspective, |i| {
+                args.get(i + 1)
+                    .and_then(|name| Projection::parse(name))
+                    .unwrap_or_else(|| usage_error("--camera needs a known projection"))
+            });
+    let settings = Settings {
+        width: projection.width(height, aspect_ratio),
+        height,
+        samples_per_pixel: 100,
+        max_depth: 50,
+        spectral: args.iter().any(|arg| arg == "--spectral"),
+    };
+    let aspect_ratio = aspect_ratio.0 as f64 / aspect_ratio.1 as f64;
+
+    // Render an animation of this many frames instead of a still
+    let frames = args.iter().position(|arg| arg == "--frames").map(|i| {
+        args.get(i + 1)
+            .and_then(|frames| frames.parse::<usize>().ok())
+            .filter(|&frames| frames > 0)
+            .unwrap_or_else(|| usage_error("--frames needs a positive number of frames"))
+    });
+
+    let sample_dist = Uniform::new(0.0, 1.0);
+    let mut rng = rand::thread_rng();
+    let Scene { world, orbit } = random_scene(&sample_dist, &mut rng);
+
+    match frames {
+        None => {
+            let camera = camera_at(projection, &orbit, (0.0, 0.0), aspect_ratio);
+            let image_data = render(camera.as_ref(), &world, &settings);
+            let path = Path::new(r"./output/random-scene.png");
+            write_png(path, settings.width, settings.height, &image_data);
+        }
+        Some(frames) => {
+            let directory = Path::new(r"./output/frames");
+            std::fs::create_dir_all(directory).unwrap();
+            for frame in 0..frames {
+                // Keep the shutter open for half of each frame, and stop short
+                // of time 1.0 so the sequence loops
+                let time = frame as f64 / frames as f64;
+                let shutter = (time, time + 0.5 / frames as f64);
+                let camera = camera_at(projection, &orbit, shutter, aspect_ratio);
+
+                println!("Frame {} of {}", frame + 1, frames);
+                let image_data = render(camera.as_ref(), &world, &settings);
+                let path = directory.join(format!("frame_{:04}.png", frame + 1));
+                write_png(&path, settings.width, settings.height, &image_data);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use raytrace::material::Medium;
+
+    #[test]
+    fn media_absorb_light_from_any_surface() {
+        // Mirror ball two units away, seen from inside tinted water
+        let mut world = hittable::List::default();
+        let mirror = Arc::new(Metal::new(Color::ONE, 0.0));
+        world.add(Sphere::new(Point::new(0.0, 0.0, -3.0), 1.0, mirror));
+        let clear = Ray::new(Point::ZERO, Vec3::new(0.0, 0.0, -1.0));
+        let water = Medium::new(RefractiveIndex::Custom(1.33), Color::new(0.5, 0.0, 0.0), 0);
+        let underwater = Ray {
+            media: clear.media.entering(water),
+            ..clear
+        };
+
+        let vec_dist = Uniform::new_inclusive(-1.0, 1.0);
+        let mut rng = rand::thread_rng();
+        let seen = ray_color(&underwater, &world, 10, &vec_dist, &mut rng);
+        let expected = ray_color(&clear, &world, 10, &vec_dist, &mut rng);
+        assert!((seen.x - (-1.0_f64).exp() * expected.x).abs() < 1e-9);
+        assert!((seen.y - expected.y).abs() < 1e-9);
+    }
+}
@@ -5,20 +5,44 @@ use crate::hittable::Record;
 use crate::ray::Ray;
 use crate::vec::Color;
 
+pub mod coated;
 pub mod dielectric;
+pub mod fresnel;
+pub mod henyey_greenstein;
+pub mod isotropic;
 pub mod lambertian;
+pub mod medium;
 pub mod metal;
+pub mod microfacet;
+pub mod mix;
+pub mod normal_map;
+pub mod oren_nayar;
+pub mod principled;
+pub mod rough_conductor;
+pub mod rough_dielectric;
 
-pub use dielectric::{Dielectric, RefractiveIndex};
+pub use coated::Coated;
+pub use dielectric::{Dielectric, Dispersion, RefractiveIndex};
+pub use fresnel::{Conductor, FresnelModel};
+pub use henyey_greenstein::HenyeyGreenstein;
+pub use isotropic::Isotropic;
 pub use lambertian::Lambertian;
+pub use medium::{Crossing, Interface, Medium, MediumStack};
 pub use metal::Metal;
+pub use microfacet::Ggx;
+pub use mix::Mix;
+pub use normal_map::{NormalMapped, SurfaceDetail};
+pub use oren_nayar::OrenNayar;
+pub use principled::Principled;
+pub use rough_conductor::RoughConductor;
+pub use rough_dielectric::RoughDielectric;
 
 pub struct Scatter {
     pub ray: Ray,
     pub attenuation: Color,
 }
 
-pub trait Material {
+pub trait Material: Send + Sync {
     fn scatter(
         &self,
         r: &Ray,
@@ -26,4 +50,9 @@ pub trait Material {
         vec_dist: &Uniform<f64>,
         rng: &mut ThreadRng,
     ) -> Option<Scatter>;
+
+    /// Light given off at the hit, in addition to any scattered light
+    fn emitted(&self, _hit: &Record) -> Color {
+        Color::ZERO
+    }
 }
@@ -0,0 +1,102 @@
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::ThreadRng;
+
+use super::microfacet::Ggx;
+use super::{fresnel, Material, RefractiveIndex, Scatter};
+use crate::hittable::Record;
+use crate::ray::Ray;
+use crate::vec::Color;
+
+use std::sync::Arc;
+
+/// Thin dielectric layer, like varnish or a car's clearcoat, over another
+/// material.
+///
+/// Light either reflects off the coat, or passes through it to scatter from
+/// the base and back out. Light reflected back down by the underside of the
+/// coat is treated as absorbed.
+pub struct Coated {
+    base: Arc<dyn Material>,
+    refractive_index: RefractiveIndex,
+    distribution: Ggx,
+    /// Optical depth of the coat, per channel, at normal incidence
+    absorption: Color,
+}
+
+impl Coated {
+    pub fn new(base: Arc<dyn Material>, refractive_index: RefractiveIndex, roughness: f64) -> Self {
+        Self {
+            base,
+            refractive_index,
+            distribution: Ggx::new(roughness),
+            absorption: Color::ZERO,
+        }
+    }
+
+    /// Tint the coat. Light crossing it at normal incidence is attenuated by
+    /// `exp(-absorption)`.
+    pub fn with_absorption(mut self, absorption: Color) -> Self {
+        self.absorption = absorption;
+        self
+    }
+}
+
+impl Material for Coated {
+    fn scatter(
+        &self,
+        r: &Ray,
+        hit: &Record,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Option<Scatter> {
+        if !hit.front_face {
+            return self.base.scatter(r, hit, vec_dist, rng);
+        }
+
+        let wavelength = r.wavelengths.map(|w| w.hero());
+        let eta_ratio =
+            r.media.refractive_index().at(wavelength) / self.refractive_index.at(wavelength);
+
+        let frame = hit.frame();
+        let wo = frame.to_local(-r.direction.unit());
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        // Reflect off the coat in proportion to its reflectance
+        let coat_reflectance = fresnel::dielectric(wo.z, eta_ratio);
+        if (vec_dist.sample(rng) + 1.0) / 2.0 < coat_reflectance {
+            let u1 = (vec_dist.sample(rng) + 1.0) / 2.0;
+            let u2 = (vec_dist.sample(rng) + 1.0) / 2.0;
+            let (wi, microfacet) = self.distribution.sample_reflection(wo, u1, u2)?;
+            let fresnel = fresnel::dielectric(wo.dot(microfacet), eta_ratio) / coat_reflectance;
+            return Some(Scatter {
+                ray: r.spawn(hit.point, frame.to_world(wi)),
+                attenuation: self.distribution.shadowing(wo, wi) * fresnel * Color::ONE,
+            });
+        }
+
+        let scatter = self.base.scatter(r, hit, vec_dist, rng)?;
+        let cos_out = scatter.ray.direction.unit().dot(hit.normal);
+        if cos_out <= 0.0 {
+            return Some(scatter);
+        }
+
+        // Leaving through the coat, and absorption along the slanted paths
+        // through it, which refraction makes steeper than outside
+        let cos_inside = |cosine: f64| {
+            (1.0 - eta_ratio.powi(2) * (1.0 - cosine.powi(2)))
+                .max(0.0)
+                .sqrt()
+                .max(1.0e-4)
+        };
+        let exit = 1.0 - fresnel::dielectric(cos_out, eta_ratio);
+        let depth = 1.0 / cos_inside(wo.z) + 1.0 / cos_inside(cos_out);
+        let transmittance = (-depth * self.absorption).exp();
+
+        Some(Scatter {
+            attenuation: exit * scatter.attenuation.schur(transmittance),
+            ..scatter
+        })
+    }
+}
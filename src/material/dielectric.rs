@@ -1,20 +1,55 @@
 use rand::distributions::{Distribution, Uniform};
 use rand::rngs::ThreadRng;
 
-use super::{Material, Scatter};
+use super::fresnel::{self, FresnelModel};
+use super::{Crossing, Material, Medium, Scatter};
 use crate::hittable::Record;
 use crate::ray::Ray;
+use crate::spectrum;
 use crate::vec::Color;
 
 pub struct Dielectric {
-    /// Index of refraction
-    refractive_index: RefractiveIndex,
-    // TODO: Color
+    /// Volume enclosed by the surface
+    medium: Medium,
+    /// Color filter applied each time light refracts through the surface
+    tint: Color,
+    fresnel: FresnelModel,
 }
 
 impl Dielectric {
     pub fn new(refractive_index: RefractiveIndex) -> Self {
-        Self { refractive_index }
+        Self::colored(refractive_index, Color::ONE, Color::ZERO)
+    }
+
+    /// Colored glass or liquid. Light traveling a distance `d` inside the
+    /// medium is attenuated by `exp(-absorption * d)` per channel.
+    pub fn colored(refractive_index: RefractiveIndex, tint: Color, absorption: Color) -> Self {
+        Self {
+            medium: Medium::new(refractive_index, absorption, 0),
+            tint,
+            fresnel: FresnelModel::Schlick,
+        }
+    }
+
+    /// Split white light into colors by the material's measured wavelength
+    /// dependence, see [`RefractiveIndex::with_dispersion`]
+    pub fn with_dispersion(mut self) -> Self {
+        self.medium.refractive_index = self.medium.refractive_index.with_dispersion();
+        self
+    }
+
+    /// Set the reflectance model, which defaults to Schlick's approximation
+    pub fn with_fresnel(mut self, fresnel: FresnelModel) -> Self {
+        self.fresnel = fresnel;
+        self
+    }
+
+    /// Set the nesting priority. Where this medium overlaps a lower priority
+    /// one, it takes precedence, e.g. an air bubble (priority 1) inside ice
+    /// (priority 0).
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.medium.priority = priority;
+        self
     }
 }
 
@@ -26,12 +61,11 @@ impl Material for Dielectric {
         vec_dist: &Uniform<f64>,
         rng: &mut ThreadRng,
     ) -> Option<Scatter> {
-        // TODO: Refraction across non-vacuum boundaries
-        let refraction_ratio = if hit.front_face {
-            1.0 / f64::from(self.refractive_index)
-        } else {
-            self.refractive_index.into()
+        let interface = match Crossing::new(&self.medium, r, hit, vec_dist, rng) {
+            Crossing::PassThrough(scatter) => return Some(scatter),
+            Crossing::Interface(interface) => interface,
         };
+        let refraction_ratio = interface.eta_ratio;
 
         let unit_direction = r.direction.unit();
         let cos_theta = (-unit_direction).dot(hit.normal).min(1.0);
@@ -39,30 +73,28 @@ impl Material for Dielectric {
 
         let cannot_refract = refraction_ratio * sin_theta > 1.0;
 
-        let direction = if cannot_refract
-            || reflectance(cos_theta, refraction_ratio) > (vec_dist.sample(rng) + 1.0) / 2.0
-        {
-            // Cannot refract
-            unit_direction.reflect(&hit.normal)
-        } else {
-            unit_direction.refract(&hit.normal, refraction_ratio)
+        let reflectance = match self.fresnel {
+            FresnelModel::Schlick => fresnel::schlick(cos_theta, refraction_ratio),
+            FresnelModel::Exact => fresnel::dielectric(cos_theta, refraction_ratio),
         };
 
+        let (ray, attenuation) =
+            if cannot_refract || reflectance > (vec_dist.sample(rng) + 1.0) / 2.0 {
+                // Cannot refract
+                let direction = unit_direction.reflect(&hit.normal);
+                (interface.reflected(r, hit, direction), Color::ONE)
+            } else {
+                let direction = unit_direction.refract(&hit.normal, refraction_ratio);
+                (interface.refracted(r, hit, direction), self.tint)
+            };
+
         Some(Scatter {
-            ray: Ray::new(hit.point, direction),
-            attenuation: Color::ONE,
+            ray,
+            attenuation: attenuation.schur(interface.weight),
         })
     }
 }
 
-/// [Schlick's Approximation](https://en.wikipedia.org/wiki/Schlick%27s_approximation)
-/// for specular reflection.
-fn reflectance(cosine: f64, refractive_index: f64) -> f64 {
-    // TOOD: Update for non-vacuum interface
-    let r0 = ((1.0 - refractive_index) / (1.0 + refractive_index)).powi(2);
-    r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
-}
-
 #[derive(Debug, Clone, Copy)]
 /// Index of refraction for various materials
 pub enum RefractiveIndex {
@@ -120,9 +152,93 @@ pub enum RefractiveIndex {
     Water,
     /// User-defined refractive index
     Custom(f64),
+    /// User-defined wavelength-dependent refractive index
+    Dispersive(Dispersion),
 }
 
-// TODO: Index of refraction that varies based on frequency of light
+impl RefractiveIndex {
+    /// The same material with its measured wavelength dependence, so it splits
+    /// white light into colors. Named materials only have a single refractive
+    /// index otherwise, and those without data are returned unchanged.
+    pub fn with_dispersion(self) -> Self {
+        let dispersion = match self {
+            // Schott N-BK7
+            RefractiveIndex::CrownGlass => Some(Dispersion::Sellmeier {
+                b: [1.03961212, 0.231792344, 1.01046945],
+                c: [0.00600069867, 0.0200179144, 103.560653],
+            }),
+            RefractiveIndex::Diamond => Some(Dispersion::Sellmeier {
+                b: [0.3306, 4.3356, 0.0],
+                c: [0.030625, 0.011236, 0.0],
+            }),
+            // Schott F2
+            RefractiveIndex::FlintGlass => Some(Dispersion::Sellmeier {
+                b: [1.34533359, 0.209073176, 0.937357162],
+                c: [0.00997743871, 0.0470450767, 111.886764],
+            }),
+            RefractiveIndex::FusedSilica => Some(Dispersion::Sellmeier {
+                b: [0.6961663, 0.4079426, 0.8974794],
+                c: [0.00467914826, 0.0135120631, 97.9340025],
+            }),
+            // Ordinary ray
+            RefractiveIndex::Sapphire => Some(Dispersion::Sellmeier {
+                b: [1.4313493, 0.65054713, 5.3414021],
+                c: [0.00527992610, 0.0142382647, 325.017834],
+            }),
+            RefractiveIndex::Water => Some(Dispersion::Cauchy {
+                a: 1.3240,
+                b: 0.00310,
+            }),
+            _ => None,
+        };
+        dispersion.map_or(self, RefractiveIndex::Dispersive)
+    }
+
+    /// Wavelength dependence of the refractive index, if it has any
+    pub fn dispersion(&self) -> Option<Dispersion> {
+        match self {
+            RefractiveIndex::Dispersive(dispersion) => Some(*dispersion),
+            _ => None,
+        }
+    }
+
+    /// Refractive index at a wavelength in nanometers. Without a wavelength,
+    /// or without dispersion data, this is the nominal index.
+    pub fn at(&self, wavelength: Option<f64>) -> f64 {
+        match (self.dispersion(), wavelength) {
+            (Some(dispersion), Some(wavelength)) => dispersion.refractive_index(wavelength),
+            _ => f64::from(*self),
+        }
+    }
+}
+
+/// Empirical models of refractive index versus wavelength, with coefficients
+/// for wavelengths in micrometers
+#[derive(Debug, Clone, Copy)]
+pub enum Dispersion {
+    /// [Cauchy's equation](https://en.wikipedia.org/wiki/Cauchy%27s_equation)
+    /// n = a + b / λ²
+    Cauchy { a: f64, b: f64 },
+    /// [Sellmeier equation](https://en.wikipedia.org/wiki/Sellmeier_equation)
+    /// n² = 1 + Σ b λ² / (λ² - c)
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    /// Refractive index at a wavelength in nanometers
+    pub fn refractive_index(&self, wavelength: f64) -> f64 {
+        let lambda_squared = (wavelength / 1000.0).powi(2);
+        match self {
+            Dispersion::Cauchy { a, b } => a + b / lambda_squared,
+            Dispersion::Sellmeier { b, c } => (1.0
+                + b.iter()
+                    .zip(c)
+                    .map(|(b, c)| b * lambda_squared / (lambda_squared - c))
+                    .sum::<f64>())
+            .sqrt(),
+        }
+    }
+}
 
 impl From<RefractiveIndex> for f64 {
     fn from(ir: RefractiveIndex) -> f64 {
@@ -154,6 +270,26 @@ impl From<RefractiveIndex> for f64 {
             RefractiveIndex::VegetableOil => 1.47,
             RefractiveIndex::Water => 1.333,
             RefractiveIndex::Custom(ir) => ir,
+            RefractiveIndex::Dispersive(dispersion) => {
+                dispersion.refractive_index(spectrum::SODIUM_D)
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dispersion_is_opt_in() {
+        let glass = RefractiveIndex::CrownGlass;
+        assert!(glass.dispersion().is_none());
+        assert_eq!(glass.at(Some(400.0)), glass.at(Some(700.0)));
+
+        let prism = glass.with_dispersion();
+        assert!(prism.dispersion().is_some());
+        assert!(prism.at(Some(400.0)) > prism.at(Some(700.0)));
+        assert!((f64::from(prism) - f64::from(glass)).abs() < 0.01);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::vec::Color;
+
+/// Choice of reflectance model at a smooth interface
+#[derive(Debug, Clone, Copy)]
+pub enum FresnelModel {
+    /// Fast polynomial fit, matching the exact value at normal incidence
+    Schlick,
+    /// Full Fresnel equations for unpolarized light
+    Exact,
+}
+
+/// [Schlick's Approximation](https://en.wikipedia.org/wiki/Schlick%27s_approximation)
+/// for specular reflection, where `eta_ratio` is the incident refractive index
+/// over the transmitted refractive index.
+pub fn schlick(cos_incident: f64, eta_ratio: f64) -> f64 {
+    let r0 = ((1.0 - eta_ratio) / (1.0 + eta_ratio)).powi(2);
+    // Leaving a denser medium, the approximation holds for the transmitted angle
+    let cosine = if eta_ratio > 1.0 {
+        let sin_squared = eta_ratio.powi(2) * (1.0 - cos_incident.powi(2));
+        if sin_squared >= 1.0 {
+            return 1.0;
+        }
+        (1.0 - sin_squared).sqrt()
+    } else {
+        cos_incident
+    };
+    r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
+}
+
+/// [Fresnel equations](https://en.wikipedia.org/wiki/Fresnel_equations) for
+/// unpolarized light at a dielectric interface, where `eta_ratio` is the
+/// incident refractive index over the transmitted refractive index. Total
+/// internal reflection gives 1.0.
+pub fn dielectric(cos_incident: f64, eta_ratio: f64) -> f64 {
+    let sin_transmitted_squared = eta_ratio.powi(2) * (1.0 - cos_incident.powi(2));
+    if sin_transmitted_squared >= 1.0 {
+        return 1.0;
+    }
+    let cos_transmitted = (1.0 - sin_transmitted_squared).sqrt();
+
+    let parallel =
+        (cos_incident - eta_ratio * cos_transmitted) / (cos_incident + eta_ratio * cos_transmitted);
+    let perpendicular =
+        (eta_ratio * cos_incident - cos_transmitted) / (eta_ratio * cos_incident + cos_transmitted);
+    (parallel.powi(2) + perpendicular.powi(2)) / 2.0
+}
+
+/// Fresnel reflectance of a conductor with complex refractive index
+/// `eta + i k`, relative to the medium outside, for each color channel
+pub fn conductor(cos_incident: f64, eta: Color, k: Color) -> Color {
+    let cos_squared = cos_incident.powi(2);
+    let sin_squared = 1.0 - cos_squared;
+
+    let channel = |eta: f64, k: f64| {
+        let t0 = eta.powi(2) - k.powi(2) - sin_squared;
+        let a2_plus_b2 = (t0.powi(2) + 4.0 * eta.powi(2) * k.powi(2)).sqrt();
+        let t1 = a2_plus_b2 + cos_squared;
+        let a = (0.5 * (a2_plus_b2 + t0)).max(0.0).sqrt();
+        let t2 = 2.0 * cos_incident * a;
+        let perpendicular = (t1 - t2) / (t1 + t2);
+
+        let t3 = cos_squared * a2_plus_b2 + sin_squared.powi(2);
+        let t4 = t2 * sin_squared;
+        let parallel = perpendicular * (t3 - t4) / (t3 + t4);
+        (parallel + perpendicular) / 2.0
+    };
+
+    Color::new(
+        channel(eta.x, k.x),
+        channel(eta.y, k.y),
+        channel(eta.z, k.z),
+    )
+}
+
+/// Measured complex refractive indices of metals, sampled at red, green and
+/// blue wavelengths
+#[derive(Debug, Clone, Copy)]
+pub enum Conductor {
+    Aluminum,
+    Copper,
+    Gold,
+    Silver,
+    /// User-defined real part `eta` and extinction coefficient `k`
+    Custom {
+        eta: Color,
+        k: Color,
+    },
+}
+
+impl Conductor {
+    /// Real part of the refractive index and the extinction coefficient
+    pub fn eta_k(&self) -> (Color, Color) {
+        match *self {
+            Conductor::Aluminum => (
+                Color::new(1.657, 0.880, 0.521),
+                Color::new(9.224, 6.270, 4.837),
+            ),
+            Conductor::Copper => (
+                Color::new(0.200, 0.924, 1.102),
+                Color::new(3.912, 2.452, 2.142),
+            ),
+            Conductor::Gold => (
+                Color::new(0.143, 0.374, 1.442),
+                Color::new(3.983, 2.385, 1.603),
+            ),
+            Conductor::Silver => (
+                Color::new(0.155, 0.117, 0.138),
+                Color::new(4.828, 3.122, 2.147),
+            ),
+            Conductor::Custom { eta, k } => (eta, k),
+        }
+    }
+
+    /// Reflectance for light arriving from a medium with refractive index
+    /// `outside`
+    pub fn reflectance(&self, cos_incident: f64, outside: f64, model: FresnelModel) -> Color {
+        let (eta, k) = self.eta_k();
+        let (eta, k) = (eta / outside, k / outside);
+        match model {
+            FresnelModel::Exact => conductor(cos_incident, eta, k),
+            FresnelModel::Schlick => {
+                let r0 = conductor(1.0, eta, k);
+                r0 + (1.0 - cos_incident).powi(5) * (Color::ONE - r0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normal_reflectance(n: f64) -> f64 {
+        ((n - 1.0) / (n + 1.0)).powi(2)
+    }
+
+    #[test]
+    fn normal_incidence_matches_closed_form() {
+        for &n in &[1.33, 1.5, 2.42] {
+            let expected = normal_reflectance(n);
+            // Entering and leaving reflect equally head on
+            assert!((dielectric(1.0, 1.0 / n) - expected).abs() < 1e-12);
+            assert!((dielectric(1.0, n) - expected).abs() < 1e-12);
+            assert!((schlick(1.0, 1.0 / n) - expected).abs() < 1e-12);
+            assert!((schlick(1.0, n) - expected).abs() < 1e-12);
+
+            let without_extinction = conductor(1.0, Color::new(n, n, n), Color::ZERO);
+            assert!((without_extinction.x - expected).abs() < 1e-12);
+        }
+
+        let (eta, k) = Conductor::Gold.eta_k();
+        let gold = conductor(1.0, eta, k);
+        let expected =
+            ((eta.x - 1.0).powi(2) + k.x.powi(2)) / ((eta.x + 1.0).powi(2) + k.x.powi(2));
+        assert!((gold.x - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn total_internal_reflection_reflects_everything() {
+        // Past the critical angle of about 42° leaving glass
+        let cos_incident = 60.0_f64.to_radians().cos();
+        assert_eq!(dielectric(cos_incident, 1.5), 1.0);
+        assert_eq!(schlick(cos_incident, 1.5), 1.0);
+    }
+
+    #[test]
+    fn schlick_approximates_exact() {
+        for &eta in &[1.5, 1.0 / 1.5, 1.33, 1.0 / 1.33] {
+            for i in 0..=100 {
+                let cos_incident = i as f64 / 100.0;
+                let error = (schlick(cos_incident, eta) - dielectric(cos_incident, eta)).abs();
+                assert!(error < 0.07, "eta {} cos {}: {}", eta, cos_incident, error);
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::ThreadRng;
+
+use super::{Material, Scatter};
+use crate::hittable::Record;
+use crate::ray::Ray;
+use crate::vec::{Color, Frame, Vec3};
+
+use std::f64::consts::PI;
+
+/// [Henyey-Greenstein](https://www.astro.umd.edu/~jph/HG_note.pdf) phase
+/// function for participating media that scatter preferentially forward or
+/// backward
+pub struct HenyeyGreenstein {
+    albedo: Color,
+    /// Mean cosine of the scattering angle, in -1.0..1.0. Positive values
+    /// scatter forward, negative values backward and zero is isotropic.
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(albedo: Color, g: f64) -> Self {
+        Self { albedo, g }
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn scatter(
+        &self,
+        r: &Ray,
+        hit: &Record,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Option<Scatter> {
+        let u1 = (vec_dist.sample(rng) + 1.0) / 2.0;
+        let u2 = (vec_dist.sample(rng) + 1.0) / 2.0;
+
+        // Invert the cumulative distribution of the scattering angle
+        let g = self.g;
+        let cos_theta = if g.abs() < 1.0e-3 {
+            1.0 - 2.0 * u1
+        } else {
+            let s = (1.0 - g.powi(2)) / (1.0 - g + 2.0 * g * u1);
+            (1.0 + g.powi(2) - s.powi(2)) / (2.0 * g)
+        }
+        .clamp(-1.0, 1.0);
+        let sin_theta = (1.0 - cos_theta.powi(2)).sqrt();
+        let phi = 2.0 * PI * u2;
+
+        let forward = Frame::new(r.direction.unit(), Vec3::new(1.0, 0.0, 0.0));
+        let direction = forward.to_world(Vec3::new(
+            sin_theta * phi.cos(),
+            sin_theta * phi.sin(),
+            cos_theta,
+        ));
+
+        Some(Scatter {
+            ray: r.spawn(hit.point, direction),
+            attenuation: self.albedo,
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use rand::distributions::Uniform;
+use rand::rngs::ThreadRng;
+
+use super::{Material, Scatter};
+use crate::hittable::Record;
+use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
+use crate::vec::{Color, Vec3};
+
+use std::sync::Arc;
+
+/// Phase function of a participating medium that scatters equally in all
+/// directions
+pub struct Isotropic {
+    albedo: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color) -> Self {
+        Self::textured(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(
+        &self,
+        r: &Ray,
+        hit: &Record,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Option<Scatter> {
+        Some(Scatter {
+            ray: r.spawn(hit.point, Vec3::random_unit(vec_dist, rng)),
+            attenuation: self.albedo.value(hit.u, hit.v, &hit.point),
+        })
+    }
+}
@@ -4,14 +4,21 @@ use rand::rngs::ThreadRng;
 use super::{Material, Scatter};
 use crate::hittable::Record;
 use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
 use crate::vec::{Color, Vec3};
 
+use std::sync::Arc;
+
 pub struct Lambertian {
-    albedo: Color,
+    albedo: Arc<dyn Texture>,
 }
 
 impl Lambertian {
     pub fn new(albedo: Color) -> Self {
+        Self::textured(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>) -> Self {
         Self { albedo }
     }
 }
@@ -19,7 +26,7 @@ impl Lambertian {
 impl Material for Lambertian {
     fn scatter(
         &self,
-        _r: &Ray,
+        r: &Ray,
         hit: &Record,
         vec_dist: &Uniform<f64>,
         rng: &mut ThreadRng,
@@ -31,8 +38,8 @@ impl Material for Lambertian {
         }
 
         Some(Scatter {
-            ray: Ray::new(hit.point, scatter_direction),
-            attenuation: self.albedo,
+            ray: r.spawn(hit.point, scatter_direction),
+            attenuation: self.albedo.value(hit.u, hit.v, &hit.point),
         })
     }
 }
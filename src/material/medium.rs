@@ -0,0 +1,313 @@
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::ThreadRng;
+
+use super::{RefractiveIndex, Scatter};
+use crate::hittable::Record;
+use crate::ray::Ray;
+use crate::spectrum::{self, SampledWavelengths};
+use crate::vec::{Color, Vec3};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Source of unique medium identifiers
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Volume of dielectric material that a ray can be inside of
+#[derive(Debug, Clone, Copy)]
+pub struct Medium {
+    id: usize,
+    pub refractive_index: RefractiveIndex,
+    /// Beer-Lambert absorption coefficient per unit distance
+    pub absorption: Color,
+    /// Where media overlap, the one with the highest priority is considered
+    /// present and the surfaces of the others are ignored
+    pub priority: u32,
+}
+
+impl Medium {
+    pub fn new(refractive_index: RefractiveIndex, absorption: Color, priority: u32) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            refractive_index,
+            absorption,
+            priority,
+        }
+    }
+}
+
+/// Media a ray is currently inside, from outermost to innermost.
+///
+/// Implements [nested dielectrics](https://doi.org/10.1080/10867651.2002.10487555)
+/// so that overlapping geometry (a glass in water, an air bubble in ice) can
+/// be modeled without exactly coincident surfaces.
+#[derive(Debug, Clone, Copy)]
+pub struct MediumStack {
+    media: [Option<Medium>; Self::MAX_DEPTH],
+    len: usize,
+}
+
+impl Default for MediumStack {
+    fn default() -> Self {
+        Self {
+            media: [None; Self::MAX_DEPTH],
+            len: 0,
+        }
+    }
+}
+
+impl MediumStack {
+    /// Deepest nesting tracked, keeping the stack small enough to copy into
+    /// every ray. Entering another medium when the stack is full forgets the
+    /// oldest lowest priority one, or ignores the new medium if its priority
+    /// is lower still, so the medium filling space stays right as long as
+    /// possible.
+    pub const MAX_DEPTH: usize = 8;
+
+    fn iter(&self) -> impl DoubleEndedIterator<Item = &Medium> {
+        self.media[..self.len].iter().flatten()
+    }
+
+    /// The medium actually filling space at the ray's position: the highest
+    /// priority, most recently entered one. `None` means vacuum.
+    pub fn current(&self) -> Option<&Medium> {
+        self.iter()
+            .rev()
+            .fold(None, |best: Option<&Medium>, medium| match best {
+                Some(b) if b.priority >= medium.priority => Some(b),
+                _ => Some(medium),
+            })
+    }
+
+    /// Refractive index at the ray's position
+    pub fn refractive_index(&self) -> RefractiveIndex {
+        self.current()
+            .map_or(RefractiveIndex::Vacuum, |m| m.refractive_index)
+    }
+
+    /// Absorption coefficient at the ray's position
+    pub fn absorption(&self) -> Color {
+        self.current().map_or(Color::ZERO, |m| m.absorption)
+    }
+
+    /// Fraction of light left after traveling `distance` through the current
+    /// medium, whatever the path meets at the end
+    pub fn transmittance(&self, distance: f64) -> Color {
+        (-distance * self.absorption()).exp()
+    }
+
+    pub fn contains(&self, medium: &Medium) -> bool {
+        self.iter().any(|m| m.id == medium.id)
+    }
+
+    /// Whether `medium` is the one filling space at the ray's position
+    pub fn is_current(&self, medium: &Medium) -> bool {
+        self.current().is_some_and(|m| m.id == medium.id)
+    }
+
+    /// Whether entering `medium` changes the medium filling space at the ray's
+    /// position. Surfaces of media hidden by a higher priority medium are not
+    /// real interfaces.
+    pub fn is_interface(&self, medium: &Medium) -> bool {
+        self.current()
+            .is_none_or(|current| medium.priority >= current.priority)
+    }
+
+    /// Stack after the ray enters `medium`. See
+    /// [`MAX_DEPTH`](Self::MAX_DEPTH) for what happens when it is full.
+    pub fn entering(&self, medium: Medium) -> Self {
+        let mut stack = *self;
+        if stack.len == Self::MAX_DEPTH {
+            let (lowest, priority) = stack
+                .iter()
+                .map(|m| m.priority)
+                .enumerate()
+                .min_by_key(|&(_, priority)| priority)
+                .expect("full stack is not empty");
+            if medium.priority < priority {
+                return stack;
+            }
+            stack.media[lowest..].rotate_left(1);
+            stack.len -= 1;
+        }
+        stack.media[stack.len] = Some(medium);
+        stack.len += 1;
+        stack
+    }
+
+    /// Stack after the ray leaves `medium`
+    pub fn exiting(&self, medium: &Medium) -> Self {
+        let mut stack = Self::default();
+        for m in self.iter().filter(|m| m.id != medium.id) {
+            stack = stack.entering(*m);
+        }
+        stack
+    }
+}
+
+/// Outcome of a ray meeting the surface of a dielectric medium
+pub enum Crossing {
+    /// The surface is hidden by a higher priority medium, so the ray continues
+    /// straight through
+    PassThrough(Scatter),
+    /// The ray reflects or refracts at a real change of medium
+    Interface(Interface),
+}
+
+/// Optics of a real interface between two media
+pub struct Interface {
+    /// Incident refractive index over transmitted refractive index
+    pub eta_ratio: f64,
+    /// Color weight of any wavelength picked by dispersion
+    pub weight: Color,
+    /// Media the ray is in after refracting
+    refracted_media: MediumStack,
+    /// Path wavelengths after any dispersion
+    wavelengths: Option<SampledWavelengths>,
+}
+
+impl Crossing {
+    /// Work out which media meet where ray `r` hits the surface of `medium`
+    pub fn new(
+        medium: &Medium,
+        r: &Ray,
+        hit: &Record,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Self {
+        let media = &r.media;
+        let pass_through = |media: MediumStack| {
+            Crossing::PassThrough(Scatter {
+                ray: Ray {
+                    media,
+                    ..r.spawn(hit.point, r.direction)
+                },
+                attenuation: Color::ONE,
+            })
+        };
+
+        // Refractive indices on the incident and transmitted sides, and the
+        // media the ray is in after refracting
+        let (incident, transmitted, refracted_media) = if hit.front_face {
+            if !media.is_interface(medium) {
+                return pass_through(media.entering(*medium));
+            }
+            (
+                media.refractive_index(),
+                medium.refractive_index,
+                media.entering(*medium),
+            )
+        } else if media.contains(medium) {
+            let outside = media.exiting(medium);
+            if !media.is_current(medium) {
+                return pass_through(outside);
+            }
+            (medium.refractive_index, outside.refractive_index(), outside)
+        } else {
+            // The path started inside this medium without entering it
+            (medium.refractive_index, media.refractive_index(), *media)
+        };
+
+        // Dispersion splits white light, so the path continues with a single
+        // wavelength. Without spectral rendering, pick one at random and
+        // weight it by its color.
+        let mut wavelengths = r.wavelengths;
+        let mut dispersion_weight = Color::ONE;
+        if incident.dispersion().is_some() || transmitted.dispersion().is_some() {
+            match &mut wavelengths {
+                Some(wavelengths) => wavelengths.terminate_secondary(),
+                None => {
+                    let sampled = spectrum::sample_wavelength((vec_dist.sample(rng) + 1.0) / 2.0);
+                    wavelengths = Some(SampledWavelengths::single(sampled));
+                    dispersion_weight = spectrum::wavelength_to_rgb(sampled);
+                }
+            }
+        }
+        let wavelength = wavelengths.map(|w| w.hero());
+
+        Crossing::Interface(Interface {
+            eta_ratio: incident.at(wavelength) / transmitted.at(wavelength),
+            weight: dispersion_weight,
+            refracted_media,
+            wavelengths,
+        })
+    }
+}
+
+impl Interface {
+    /// Continue the path on the incident side of the surface
+    pub fn reflected(&self, r: &Ray, hit: &Record, direction: Vec3) -> Ray {
+        Ray {
+            wavelengths: self.wavelengths,
+            ..r.spawn(hit.point, direction)
+        }
+    }
+
+    /// Continue the path on the transmitted side of the surface
+    pub fn refracted(&self, r: &Ray, hit: &Record, direction: Vec3) -> Ray {
+        Ray {
+            media: self.refracted_media,
+            wavelengths: self.wavelengths,
+            ..r.spawn(hit.point, direction)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn medium(priority: u32) -> Medium {
+        Medium::new(RefractiveIndex::Water, Color::ZERO, priority)
+    }
+
+    #[test]
+    fn stack_tracks_entered_media() {
+        let (water, glass) = (medium(0), medium(0));
+        let stack = MediumStack::default();
+        assert!(stack.current().is_none());
+
+        let stack = stack.entering(water).entering(glass);
+        assert!(stack.contains(&water) && stack.is_current(&glass));
+
+        let stack = stack.exiting(&glass);
+        assert!(!stack.contains(&glass) && stack.is_current(&water));
+        assert!(stack.exiting(&water).current().is_none());
+    }
+
+    #[test]
+    fn highest_priority_medium_fills_space() {
+        let (ice, bubble, water) = (medium(1), medium(2), medium(1));
+        let stack = MediumStack::default().entering(ice).entering(bubble);
+        assert!(stack.is_current(&bubble));
+
+        // Lower priority media entered later stay hidden
+        let stack = stack.entering(medium(0));
+        assert!(stack.is_current(&bubble));
+        assert!(!stack.is_interface(&ice));
+
+        // Equal priority goes to the most recent
+        let stack = stack.exiting(&bubble).entering(water);
+        assert!(stack.is_current(&water));
+        assert!(stack.is_interface(&medium(1)));
+    }
+
+    #[test]
+    fn full_stack_forgets_lowest_priority_media() {
+        let outermost = medium(1);
+        let mut stack = MediumStack::default().entering(outermost);
+        for _ in 1..MediumStack::MAX_DEPTH {
+            stack = stack.entering(medium(2));
+        }
+
+        // Lower priority than everything tracked, so ignored
+        let ignored = medium(0);
+        let full = stack.entering(ignored);
+        assert!(!full.contains(&ignored) && full.contains(&outermost));
+
+        // Replaces the outermost, lowest priority medium
+        let innermost = medium(2);
+        let full = stack.entering(innermost);
+        assert!(full.is_current(&innermost) && !full.contains(&outermost));
+        assert_eq!(full.iter().count(), MediumStack::MAX_DEPTH);
+    }
+}
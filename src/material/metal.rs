@@ -1,7 +1,7 @@
 use rand::distributions::Uniform;
 use rand::rngs::ThreadRng;
 
-use super::{Material, Scatter};
+use super::{Conductor, FresnelModel, Material, Scatter};
 use crate::hittable::Record;
 use crate::ray::Ray;
 use crate::vec::{Color, Vec3};
@@ -10,11 +10,35 @@ pub struct Metal {
     albedo: Color,
     // TODO: Float type clamped to 0.0 to 1.0
     fuzz: f64,
+    /// Angle-dependent reflectance of a real metal, scaled by the albedo
+    conductor: Option<Conductor>,
+    fresnel: FresnelModel,
 }
 
 impl Metal {
     pub fn new(albedo: Color, fuzz: f64) -> Self {
-        Self { albedo, fuzz }
+        Self {
+            albedo,
+            fuzz,
+            conductor: None,
+            fresnel: FresnelModel::Exact,
+        }
+    }
+
+    /// Metal with reflectance from its measured complex refractive index
+    pub fn conductor(conductor: Conductor, fuzz: f64) -> Self {
+        Self {
+            albedo: Color::ONE,
+            fuzz,
+            conductor: Some(conductor),
+            fresnel: FresnelModel::Exact,
+        }
+    }
+
+    /// Set the reflectance model for conductors, which defaults to exact
+    pub fn with_fresnel(mut self, fresnel: FresnelModel) -> Self {
+        self.fresnel = fresnel;
+        self
     }
 }
 
@@ -26,13 +50,27 @@ impl Material for Metal {
         vec_dist: &Uniform<f64>,
         rng: &mut ThreadRng,
     ) -> Option<Scatter> {
-        let reflected = r.direction.unit().reflect(&hit.normal);
+        let unit_direction = r.direction.unit();
+        let reflected = unit_direction.reflect(&hit.normal);
         let target = reflected + self.fuzz * Vec3::random_inside_unit(vec_dist, rng);
 
+        let attenuation = match self.conductor {
+            Some(conductor) => {
+                let cos_theta = (-unit_direction).dot(hit.normal).clamp(0.0, 1.0);
+                let outside = r
+                    .media
+                    .refractive_index()
+                    .at(r.wavelengths.map(|w| w.hero()));
+                self.albedo
+                    .schur(conductor.reflectance(cos_theta, outside, self.fresnel))
+            }
+            None => self.albedo,
+        };
+
         if reflected.dot(hit.normal) > 0.0 {
             Some(Scatter {
-                ray: Ray::new(hit.point, target),
-                attenuation: self.albedo,
+                ray: r.spawn(hit.point, target),
+                attenuation,
             })
         } else {
             None
@@ -0,0 +1,101 @@
+use std::f64::consts::PI;
+
+use crate::vec::Vec3;
+
+/// [GGX / Trowbridge-Reitz](https://doi.org/10.2312/EGWR/EGSR07/195-206)
+/// distribution of microfacet normals.
+///
+/// All directions are in a local shading frame with the surface normal along
+/// +Z and the `u` tangent along +X.
+#[derive(Debug, Clone, Copy)]
+pub struct Ggx {
+    alpha_x: f64,
+    alpha_y: f64,
+}
+
+impl Ggx {
+    /// Smallest alpha, keeping near-mirror surfaces numerically stable
+    const MIN_ALPHA: f64 = 1.0e-4;
+
+    /// Isotropic distribution from a perceptual roughness in 0.0..=1.0
+    pub fn new(roughness: f64) -> Self {
+        Self::anisotropic(roughness, roughness)
+    }
+
+    /// Distribution with separate roughness along the `u` and `v` tangents,
+    /// e.g. for brushed metal
+    pub fn anisotropic(roughness_u: f64, roughness_v: f64) -> Self {
+        Self {
+            alpha_x: roughness_u.powi(2).max(Self::MIN_ALPHA),
+            alpha_y: roughness_v.powi(2).max(Self::MIN_ALPHA),
+        }
+    }
+
+    /// Smith auxiliary function for the masking of direction `w`
+    fn lambda(&self, w: Vec3) -> f64 {
+        if w.z == 0.0 {
+            return f64::INFINITY;
+        }
+        let tan_squared =
+            ((self.alpha_x * w.x).powi(2) + (self.alpha_y * w.y).powi(2)) / w.z.powi(2);
+        ((1.0 + tan_squared).sqrt() - 1.0) / 2.0
+    }
+
+    /// Fraction of microfacets visible from direction `w`
+    pub fn g1(&self, w: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// Height-correlated fraction of microfacets visible from both `wo` and
+    /// `wi`
+    pub fn g(&self, wo: Vec3, wi: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Sample a direction reflected about a visible microfacet normal,
+    /// returning the direction and the microfacet normal, or `None` if the
+    /// reflection goes below the surface. The estimator weight of the
+    /// reflection, before Fresnel, is `self.shadowing(wo, wi)`.
+    pub fn sample_reflection(&self, wo: Vec3, u1: f64, u2: f64) -> Option<(Vec3, Vec3)> {
+        let microfacet = self.sample_visible_normal(wo, u1, u2);
+        let wi = (-wo).reflect(&microfacet);
+        if wi.z > 0.0 {
+            Some((wi, microfacet))
+        } else {
+            None
+        }
+    }
+
+    /// Shadowing of `wi` given that `wo` sees the microfacet
+    pub fn shadowing(&self, wo: Vec3, wi: Vec3) -> f64 {
+        self.g(wo, wi) / self.g1(wo)
+    }
+
+    /// Sample a microfacet normal in proportion to how much of it is visible
+    /// from `wo`, with `u1` and `u2` uniform in 0.0..1.0. From
+    /// [Heitz 2018](https://jcgt.org/published/0007/04/01/).
+    pub fn sample_visible_normal(&self, wo: Vec3, u1: f64, u2: f64) -> Vec3 {
+        // Stretch the view direction to the hemisphere configuration
+        let vh = Vec3::new(self.alpha_x * wo.x, self.alpha_y * wo.y, wo.z).unit();
+
+        let length_squared = vh.x.powi(2) + vh.y.powi(2);
+        let t1 = if length_squared > 0.0 {
+            Vec3::new(-vh.y, vh.x, 0.0) / length_squared.sqrt()
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let t2 = vh.cross(t1);
+
+        // Point on the projected area of the visible hemisphere
+        let r = u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z);
+        let p2 = (1.0 - s) * (1.0 - p1.powi(2)).sqrt() + s * r * phi.sin();
+
+        let nh = p1 * t1 + p2 * t2 + (1.0 - p1.powi(2) - p2.powi(2)).max(0.0).sqrt() * vh;
+
+        // Unstretch back to the ellipsoid configuration
+        Vec3::new(self.alpha_x * nh.x, self.alpha_y * nh.y, nh.z.max(0.0)).unit()
+    }
+}
@@ -0,0 +1,49 @@
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::ThreadRng;
+
+use super::{Material, Scatter};
+use crate::hittable::Record;
+use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
+use crate::vec::Color;
+
+use std::sync::Arc;
+
+/// Blend of two materials, picking one at random per scattering event
+pub struct Mix {
+    a: Arc<dyn Material>,
+    b: Arc<dyn Material>,
+    /// Probability of scattering from `b`, read from the red channel
+    weight: Arc<dyn Texture>,
+}
+
+impl Mix {
+    pub fn new(a: Arc<dyn Material>, b: Arc<dyn Material>, weight: f64) -> Self {
+        Self::textured(
+            a,
+            b,
+            Arc::new(SolidColor::new(Color::new(weight, weight, weight))),
+        )
+    }
+
+    pub fn textured(a: Arc<dyn Material>, b: Arc<dyn Material>, weight: Arc<dyn Texture>) -> Self {
+        Self { a, b, weight }
+    }
+}
+
+impl Material for Mix {
+    fn scatter(
+        &self,
+        r: &Ray,
+        hit: &Record,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Option<Scatter> {
+        let weight = self.weight.value(hit.u, hit.v, &hit.point).x;
+        if (vec_dist.sample(rng) + 1.0) / 2.0 < weight {
+            self.b.scatter(r, hit, vec_dist, rng)
+        } else {
+            self.a.scatter(r, hit, vec_dist, rng)
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use rand::distributions::Uniform;
+use rand::rngs::ThreadRng;
+
+use super::{Material, Scatter};
+use crate::hittable::Record;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::vec::Vec3;
+
+use std::sync::Arc;
+
+/// Texture-driven change to the shading normal
+pub enum SurfaceDetail {
+    /// Tangent-space normal map with XYZ encoded as RGB in 0.0..=1.0
+    NormalMap(Arc<dyn Texture>),
+    /// Scalar height field read from the red channel, scaled by `strength`
+    BumpMap {
+        height: Arc<dyn Texture>,
+        strength: f64,
+    },
+}
+
+/// Wraps another material, perturbing the hit normal before it scatters
+pub struct NormalMapped {
+    material: Arc<dyn Material>,
+    detail: SurfaceDetail,
+}
+
+impl NormalMapped {
+    /// Finite-difference step in texture coordinates for bump maps
+    const BUMP_DELTA: f64 = 1.0e-3;
+
+    pub fn new(material: Arc<dyn Material>, detail: SurfaceDetail) -> Self {
+        Self { material, detail }
+    }
+
+    fn perturbed_normal(&self, hit: &Record) -> Vec3 {
+        let normal = hit.normal;
+        let normal = match &self.detail {
+            SurfaceDetail::NormalMap(map) => {
+                // Gram-Schmidt tangent frame around the shading normal
+                let tangent = (hit.dpdu - hit.dpdu.dot(normal) * normal).unit();
+                let mut bitangent = normal.cross(tangent);
+                if bitangent.dot(hit.dpdv) < 0.0 {
+                    bitangent = -bitangent;
+                }
+                let m = 2.0 * map.value(hit.u, hit.v, &hit.point) - Vec3::ONE;
+                m.x * tangent + m.y * bitangent + m.z * normal
+            }
+            SurfaceDetail::BumpMap { height, strength } => {
+                let du = Self::BUMP_DELTA;
+                let dv = Self::BUMP_DELTA;
+                let h = |u: f64, v: f64, point: Vec3| strength * height.value(u, v, &point).x;
+
+                let h0 = h(hit.u, hit.v, hit.point);
+                let h_u = h(hit.u + du, hit.v, hit.point + du * hit.dpdu);
+                let h_v = h(hit.u, hit.v + dv, hit.point + dv * hit.dpdv);
+
+                let dpdu = hit.dpdu + (h_u - h0) / du * normal;
+                let dpdv = hit.dpdv + (h_v - h0) / dv * normal;
+                let bumped = dpdu.cross(dpdv);
+                if bumped.dot(normal) < 0.0 {
+                    -bumped
+                } else {
+                    bumped
+                }
+            }
+        };
+
+        // Fall back to the true normal rather than shade from below the surface
+        if normal.near_zero() || normal.dot(hit.normal) <= 0.0 {
+            hit.normal
+        } else {
+            normal.unit()
+        }
+    }
+}
+
+impl Material for NormalMapped {
+    fn scatter(
+        &self,
+        r: &Ray,
+        hit: &Record,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Option<Scatter> {
+        let mut perturbed = hit.clone();
+        perturbed.normal = self.perturbed_normal(hit);
+        self.material.scatter(r, &perturbed, vec_dist, rng)
+    }
+}
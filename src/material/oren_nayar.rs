@@ -0,0 +1,80 @@
+use rand::distributions::Uniform;
+use rand::rngs::ThreadRng;
+
+use super::{Material, Scatter};
+use crate::hittable::Record;
+use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
+use crate::vec::{Color, Vec3};
+
+use std::sync::Arc;
+
+/// [Oren-Nayar](https://en.wikipedia.org/wiki/Oren%E2%80%93Nayar_reflectance_model)
+/// rough diffuse reflection, for clay, concrete and fabric. Zero roughness is
+/// the same as `Lambertian`.
+pub struct OrenNayar {
+    albedo: Arc<dyn Texture>,
+    a: f64,
+    b: f64,
+}
+
+impl OrenNayar {
+    /// `sigma` is the standard deviation of the facet slope angle, in degrees
+    pub fn new(albedo: Color, sigma: f64) -> Self {
+        Self::textured(Arc::new(SolidColor::new(albedo)), sigma)
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>, sigma: f64) -> Self {
+        let sigma_squared = sigma.to_radians().powi(2);
+        Self {
+            albedo,
+            a: 1.0 - sigma_squared / (2.0 * (sigma_squared + 0.33)),
+            b: 0.45 * sigma_squared / (sigma_squared + 0.09),
+        }
+    }
+}
+
+impl Material for OrenNayar {
+    fn scatter(
+        &self,
+        r: &Ray,
+        hit: &Record,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Option<Scatter> {
+        // Cosine-weighted like Lambertian, so the weight is the BRDF times pi
+        let mut scatter_direction = hit.normal + Vec3::random_unit(vec_dist, rng);
+
+        if scatter_direction.near_zero() {
+            scatter_direction = hit.normal;
+        }
+
+        let frame = hit.frame();
+        let wo = frame.to_local(-r.direction.unit());
+        let wi = frame.to_local(scatter_direction.unit());
+
+        let sin_o = (1.0 - wo.z.powi(2)).max(0.0).sqrt();
+        let sin_i = (1.0 - wi.z.powi(2)).max(0.0).sqrt();
+
+        // Cosine of the azimuthal angle between the two directions
+        let cos_phi = if sin_o > 1.0e-4 && sin_i > 1.0e-4 {
+            ((wo.x * wi.x + wo.y * wi.y) / (sin_o * sin_i)).max(0.0)
+        } else {
+            0.0
+        };
+        // sin(alpha) tan(beta) for alpha the larger and beta the smaller angle
+        // from the normal
+        let sin_alpha_tan_beta = if wi.z.abs() > wo.z.abs() {
+            sin_o * sin_i / wi.z.abs()
+        } else {
+            sin_i * sin_o / wo.z.abs().max(1.0e-4)
+        };
+
+        let albedo = self.albedo.value(hit.u, hit.v, &hit.point);
+
+        Some(Scatter {
+            ray: r.spawn(hit.point, scatter_direction),
+            attenuation: (self.a + self.b * cos_phi * sin_alpha_tan_beta) * albedo,
+        })
+    }
+}
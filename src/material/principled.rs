@@ -0,0 +1,410 @@
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::ThreadRng;
+
+use super::microfacet::Ggx;
+use super::{rough_dielectric, Material, Medium, RefractiveIndex, Scatter};
+use crate::hittable::Record;
+use crate::ray::Ray;
+use crate::texture::{ImageTexture, SolidColor, Texture, WrapMode};
+use crate::vec::{Color, Vec3};
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// [Disney principled BSDF](https://disneyanimation.com/publications/physically-based-shading-at-disney/)
+/// covering diffuse, metal, glass and coated surfaces with one set of
+/// parameters.
+///
+/// Every parameter is a texture. Scalar parameters, all in 0.0..=1.0, are read
+/// from the red channel.
+pub struct Principled {
+    base_color: Arc<dyn Texture>,
+    /// Blend from dielectric to metal
+    metallic: Arc<dyn Texture>,
+    roughness: Arc<dyn Texture>,
+    /// Strength of dielectric specular reflection, where 0.5 is 4% at normal
+    /// incidence
+    specular: Arc<dyn Texture>,
+    /// Soft retroreflection at grazing angles, as for cloth
+    sheen: Arc<dyn Texture>,
+    /// Strength of a clear varnish layer on top
+    clearcoat: Arc<dyn Texture>,
+    clearcoat_roughness: Arc<dyn Texture>,
+    /// Fraction of the dielectric base that transmits light like glass
+    transmission: Arc<dyn Texture>,
+    /// Interior for transmitted light
+    medium: Medium,
+}
+
+impl Principled {
+    /// Refractive index of the clearcoat layer
+    const CLEARCOAT_ETA: f64 = 1.5;
+
+    /// Rough dielectric surface with the other parameters at their defaults
+    pub fn new(base_color: Arc<dyn Texture>) -> Self {
+        Self {
+            base_color,
+            metallic: constant(0.0),
+            roughness: constant(0.5),
+            specular: constant(0.5),
+            sheen: constant(0.0),
+            clearcoat: constant(0.0),
+            clearcoat_roughness: constant(0.03),
+            transmission: constant(0.0),
+            medium: Medium::new(RefractiveIndex::Custom(1.5), Color::ZERO, 0),
+        }
+    }
+
+    pub fn with_metallic(mut self, metallic: Arc<dyn Texture>) -> Self {
+        self.metallic = metallic;
+        self
+    }
+
+    pub fn with_roughness(mut self, roughness: Arc<dyn Texture>) -> Self {
+        self.roughness = roughness;
+        self
+    }
+
+    pub fn with_specular(mut self, specular: Arc<dyn Texture>) -> Self {
+        self.specular = specular;
+        self
+    }
+
+    pub fn with_sheen(mut self, sheen: Arc<dyn Texture>) -> Self {
+        self.sheen = sheen;
+        self
+    }
+
+    pub fn with_clearcoat(
+        mut self,
+        clearcoat: Arc<dyn Texture>,
+        clearcoat_roughness: Arc<dyn Texture>,
+    ) -> Self {
+        self.clearcoat = clearcoat;
+        self.clearcoat_roughness = clearcoat_roughness;
+        self
+    }
+
+    pub fn with_transmission(
+        mut self,
+        transmission: Arc<dyn Texture>,
+        refractive_index: RefractiveIndex,
+    ) -> Self {
+        self.transmission = transmission;
+        self.medium = Medium::new(refractive_index, Color::ZERO, 0);
+        self
+    }
+
+    /// Read a material description with one `parameter = value` per line and
+    /// `#` starting a comment, such as
+    ///
+    /// ```text
+    /// base_color = textures/brick.png
+    /// roughness = 0.7
+    /// clearcoat = 1.0
+    /// ```
+    ///
+    /// Parameters are named after the fields, plus `ior` for the refractive
+    /// index of transmitted light. Values are a number, three numbers for a
+    /// color, or the path of a PNG texture relative to the description.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let invalid = |line: usize, message: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}:{}: {}", path.display(), line + 1, message),
+            )
+        };
+
+        let mut material = Self::new(constant(0.8));
+        let mut refractive_index = 1.5;
+        for (line, text) in fs::read_to_string(path)?.lines().enumerate() {
+            let text = text.split('#').next().unwrap_or("").trim();
+            if text.is_empty() {
+                continue;
+            }
+            let (name, value) = match text.split_once('=') {
+                Some((name, value)) => (name.trim(), value.trim()),
+                None => return Err(invalid(line, format!("expected `=` in {:?}", text))),
+            };
+
+            if name == "ior" {
+                refractive_index = value
+                    .parse()
+                    .map_err(|_| invalid(line, format!("bad refractive index {:?}", value)))?;
+                continue;
+            }
+            let texture = parameter(directory, value, name == "base_color")
+                .map_err(|error| invalid(line, error.to_string()))?;
+            match name {
+                "base_color" => material.base_color = texture,
+                "metallic" => material.metallic = texture,
+                "roughness" => material.roughness = texture,
+                "specular" => material.specular = texture,
+                "sheen" => material.sheen = texture,
+                "clearcoat" => material.clearcoat = texture,
+                "clearcoat_roughness" => material.clearcoat_roughness = texture,
+                "transmission" => material.transmission = texture,
+                _ => return Err(invalid(line, format!("unknown parameter {:?}", name))),
+            }
+        }
+
+        material.medium = Medium::new(RefractiveIndex::Custom(refractive_index), Color::ZERO, 0);
+        Ok(material)
+    }
+}
+
+fn constant(value: f64) -> Arc<dyn Texture> {
+    Arc::new(SolidColor::new(Color::new(value, value, value)))
+}
+
+/// Texture for a value in a material description: a number, a color, or a PNG
+/// file holding sRGB colors or linear scalars
+fn parameter(directory: &Path, value: &str, color: bool) -> io::Result<Arc<dyn Texture>> {
+    let numbers: Result<Vec<f64>, _> = value.split_whitespace().map(str::parse).collect();
+    match numbers.as_deref() {
+        Ok([value]) => Ok(constant(*value)),
+        Ok([r, g, b]) => Ok(Arc::new(SolidColor::new(Color::new(*r, *g, *b)))),
+        _ => {
+            let path = directory.join(value);
+            let image = if color {
+                ImageTexture::open(path, WrapMode::Repeat)
+            } else {
+                ImageTexture::open_linear(path, WrapMode::Repeat)
+            };
+            match image {
+                Ok(image) => Ok(Arc::new(image)),
+                Err(png::DecodingError::IoError(error)) => Err(error),
+                Err(error) => Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+            }
+        }
+    }
+}
+
+/// Fraction of light reflected by the unnormalized Burley diffuse lobe for
+/// light leaving at `cos_o` to the normal.
+///
+/// With 2 cos²θd = 1 + ωi·ωo, the lobe is a polynomial in ωi·ωo and the Schlick
+/// weight of ωi, so its average over cosine-distributed ωi has a closed form
+/// in the moments E[μᵏ (1 - μ)⁵] = 2 (k + 1)! 5! / (k + 7)!.
+fn burley_albedo(roughness: f64, cos_o: f64) -> f64 {
+    const MOMENTS: [f64; 3] = [1.0 / 21.0, 1.0 / 84.0, 1.0 / 252.0];
+    // fd90 - 1 = a + b ωi·ωo
+    let (a, b) = (roughness - 0.5, roughness);
+    let sin2_o = 1.0 - cos_o.powi(2);
+
+    // Averages of (fd90 - 1), and of it and its square times the weight of ωi
+    let mean = a + b * cos_o * 2.0 / 3.0;
+    let weighted = a * MOMENTS[0] + b * cos_o * MOMENTS[1];
+    let weighted_square = a.powi(2) * MOMENTS[0]
+        + 2.0 * a * b * cos_o * MOMENTS[1]
+        + b.powi(2) * (cos_o.powi(2) * MOMENTS[2] + sin2_o / 2.0 * (MOMENTS[0] - MOMENTS[2]));
+
+    let weight_o = schlick_weight(cos_o);
+    1.0 + weighted + weight_o * (mean + weighted_square)
+}
+
+/// Schlick Fresnel weight (1 - cos)^5
+fn schlick_weight(cosine: f64) -> f64 {
+    (1.0 - cosine).clamp(0.0, 1.0).powi(5)
+}
+
+impl Material for Principled {
+    fn scatter(
+        &self,
+        r: &Ray,
+        hit: &Record,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Option<Scatter> {
+        let value = |texture: &Arc<dyn Texture>| texture.value(hit.u, hit.v, &hit.point);
+        let scalar = |texture: &Arc<dyn Texture>| value(texture).x.clamp(0.0, 1.0);
+        let mut uniform = || (vec_dist.sample(rng) + 1.0) / 2.0;
+
+        let base_color = value(&self.base_color);
+        let metallic = scalar(&self.metallic);
+        let roughness = scalar(&self.roughness);
+        let specular = scalar(&self.specular);
+        let sheen = scalar(&self.sheen);
+        let clearcoat = scalar(&self.clearcoat);
+        let clearcoat_roughness = scalar(&self.clearcoat_roughness);
+        let transmission = scalar(&self.transmission);
+
+        let frame = hit.frame();
+        let wo = frame.to_local(-r.direction.unit());
+        let distribution = Ggx::new(roughness);
+
+        // Each lobe is picked with probability equal to its share of the
+        // reflected energy, so the weights only hold what sampling leaves out
+        let reflect = |distribution: &Ggx, u1: f64, u2: f64, fresnel: &dyn Fn(f64) -> Color| {
+            let (wi, microfacet) = distribution.sample_reflection(wo, u1, u2)?;
+            Some(Scatter {
+                ray: r.spawn(hit.point, frame.to_world(wi)),
+                attenuation: distribution.shadowing(wo, wi) * fresnel(wo.dot(microfacet)),
+            })
+        };
+
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        if hit.front_face {
+            // Clear varnish over everything else
+            let r0 = ((Self::CLEARCOAT_ETA - 1.0) / (Self::CLEARCOAT_ETA + 1.0)).powi(2);
+            let coat_reflectance = clearcoat * (r0 + (1.0 - r0) * schlick_weight(wo.z));
+            if uniform() < coat_reflectance {
+                let (u1, u2) = (uniform(), uniform());
+                return reflect(&Ggx::new(clearcoat_roughness), u1, u2, &|_| Color::ONE);
+            }
+
+            if uniform() < metallic {
+                let (u1, u2) = (uniform(), uniform());
+                return reflect(&distribution, u1, u2, &|cosine| {
+                    base_color + schlick_weight(cosine) * (Color::ONE - base_color)
+                });
+            }
+        }
+
+        // Light already inside a transmissive object can only be transmitted
+        if transmission > 0.0 && (!hit.front_face || uniform() < transmission) {
+            // Light passing through the surface takes on the base color
+            return rough_dielectric::scatter(
+                &self.medium,
+                &distribution,
+                base_color,
+                r,
+                hit,
+                vec_dist,
+                rng,
+            );
+        }
+
+        // Opaque dielectric: glossy specular over a diffuse base
+        let r0 = 0.08 * specular;
+        let specular_probability = r0 + (1.0 - r0) * schlick_weight(wo.z);
+        if uniform() < specular_probability {
+            let (u1, u2) = (uniform(), uniform());
+            return reflect(&distribution, u1, u2, &|cosine| {
+                (r0 + (1.0 - r0) * schlick_weight(cosine)) / specular_probability * Color::ONE
+            });
+        }
+
+        let mut direction = hit.normal + Vec3::random_unit(vec_dist, rng);
+        if direction.near_zero() {
+            direction = hit.normal;
+        }
+        let wi = frame.to_local(direction.unit());
+        let cos_d = (wi + wo).unit().dot(wi);
+
+        // Burley diffuse with retroreflection at grazing angles, divided by
+        // its albedo so that a white surface reflects all the light it gets
+        let fd90 = 0.5 + 2.0 * roughness * cos_d.powi(2);
+        let diffuse = (1.0 + (fd90 - 1.0) * schlick_weight(wi.z))
+            * (1.0 + (fd90 - 1.0) * schlick_weight(wo.z))
+            / burley_albedo(roughness, wo.z);
+
+        // Sheen lies over the diffuse base, taking its share of the light
+        // rather than adding to it
+        let sheen = sheen * schlick_weight(cos_d);
+        Some(Scatter {
+            ray: r.spawn(hit.point, direction),
+            attenuation: (1.0 - sheen) * diffuse * base_color + sheen * Color::ONE,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec::Point;
+
+    /// Average reflectance for light arriving at `angle` degrees from the
+    /// normal, with the material in a white furnace
+    fn albedo(material: Arc<Principled>, angle: f64) -> f64 {
+        let vec_dist = Uniform::new_inclusive(-1.0, 1.0);
+        let mut rng = rand::thread_rng();
+        let radians = angle.to_radians();
+        let r = Ray::new(
+            Point::new(-radians.sin(), 0.0, radians.cos()),
+            Vec3::new(radians.sin(), 0.0, -radians.cos()),
+        );
+        let hit = Record::new(
+            &r,
+            Point::ZERO,
+            Vec3::new(0.0, 0.0, 1.0),
+            1.0,
+            (0.5, 0.5),
+            (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+            material.clone(),
+        );
+        let samples = 20000;
+        let total = (0..samples).fold(0.0, |total, _| {
+            match material.scatter(&r, &hit, &vec_dist, &mut rng) {
+                Some(scatter) if scatter.ray.direction.dot(hit.normal) > 0.0 => {
+                    total + scatter.attenuation.x
+                }
+                _ => total,
+            }
+        });
+        total / samples as f64
+    }
+
+    #[test]
+    fn white_furnace_conserves_energy() {
+        // Rough specular reflection loses light to shadowing, so look where
+        // it hardly matters: near normal incidence, or at any angle on a
+        // smooth surface
+        let cases = [
+            (0.0, &[0.0, 30.0, 60.0, 85.0][..]),
+            (0.5, &[0.0, 30.0][..]),
+            (1.0, &[0.0, 30.0][..]),
+        ];
+        for &(roughness, angles) in &cases {
+            let material = Principled::new(constant(1.0))
+                .with_roughness(constant(roughness))
+                .with_sheen(constant(1.0))
+                .with_specular(constant(0.0));
+            let material = Arc::new(material);
+            for &angle in angles {
+                let albedo = albedo(material.clone(), angle);
+                assert!(
+                    (albedo - 1.0).abs() < 0.02,
+                    "albedo {} at {} degrees and roughness {}",
+                    albedo,
+                    angle,
+                    roughness
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn open_reads_description() {
+        let path = std::env::temp_dir().join("raytrace_principled.mat");
+        fs::write(
+            &path,
+            "# Rough red plastic\nbase_color = 0.8 0.1 0.1\nroughness = 0.7\nior = 1.3\n",
+        )
+        .unwrap();
+        let material = Principled::open(&path);
+        fs::remove_file(&path).unwrap();
+
+        let material = material.unwrap();
+        let value = |texture: &Arc<dyn Texture>| texture.value(0.0, 0.0, &Point::ZERO);
+        assert_eq!(value(&material.base_color).components(), (0.8, 0.1, 0.1));
+        assert_eq!(value(&material.roughness).x, 0.7);
+        assert_eq!(value(&material.metallic).x, 0.0);
+    }
+
+    #[test]
+    fn open_rejects_unknown_parameters() {
+        let path = std::env::temp_dir().join("raytrace_unknown.mat");
+        fs::write(&path, "shininess = 1.0\n").unwrap();
+        let error = Principled::open(&path).err().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,61 @@
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::ThreadRng;
+
+use super::microfacet::Ggx;
+use super::{Conductor, FresnelModel, Material, Scatter};
+use crate::hittable::Record;
+use crate::ray::Ray;
+
+/// Metal with a GGX microfacet surface
+pub struct RoughConductor {
+    conductor: Conductor,
+    distribution: Ggx,
+}
+
+impl RoughConductor {
+    pub fn new(conductor: Conductor, roughness: f64) -> Self {
+        Self::with_distribution(conductor, Ggx::new(roughness))
+    }
+
+    pub fn with_distribution(conductor: Conductor, distribution: Ggx) -> Self {
+        Self {
+            conductor,
+            distribution,
+        }
+    }
+}
+
+impl Material for RoughConductor {
+    fn scatter(
+        &self,
+        r: &Ray,
+        hit: &Record,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Option<Scatter> {
+        let frame = hit.frame();
+        let wo = frame.to_local(-r.direction.unit());
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        let u1 = (vec_dist.sample(rng) + 1.0) / 2.0;
+        let u2 = (vec_dist.sample(rng) + 1.0) / 2.0;
+        let (wi, microfacet) = self.distribution.sample_reflection(wo, u1, u2)?;
+
+        // Sampling visible normals leaves only Fresnel and shadowing
+        let outside = r
+            .media
+            .refractive_index()
+            .at(r.wavelengths.map(|w| w.hero()));
+        let fresnel = self
+            .conductor
+            .reflectance(wo.dot(microfacet), outside, FresnelModel::Exact);
+        let shadowing = self.distribution.shadowing(wo, wi);
+
+        Some(Scatter {
+            ray: r.spawn(hit.point, frame.to_world(wi)),
+            attenuation: shadowing * fresnel,
+        })
+    }
+}
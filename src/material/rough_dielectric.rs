@@ -0,0 +1,176 @@
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::ThreadRng;
+
+use super::microfacet::Ggx;
+use super::{fresnel, Crossing, Material, Medium, RefractiveIndex, Scatter};
+use crate::hittable::Record;
+use crate::ray::Ray;
+use crate::vec::Color;
+
+/// Frosted glass: a dielectric with a GGX microfacet surface
+pub struct RoughDielectric {
+    /// Volume enclosed by the surface
+    medium: Medium,
+    /// Color filter applied each time light refracts through the surface
+    tint: Color,
+    distribution: Ggx,
+}
+
+impl RoughDielectric {
+    pub fn new(refractive_index: RefractiveIndex, roughness: f64) -> Self {
+        Self::colored(refractive_index, roughness, Color::ONE, Color::ZERO)
+    }
+
+    /// Frosted colored glass, tinted and absorbing as for
+    /// [`Dielectric::colored`](super::Dielectric::colored)
+    pub fn colored(
+        refractive_index: RefractiveIndex,
+        roughness: f64,
+        tint: Color,
+        absorption: Color,
+    ) -> Self {
+        Self {
+            medium: Medium::new(refractive_index, absorption, 0),
+            tint,
+            distribution: Ggx::new(roughness),
+        }
+    }
+
+    /// Set the nesting priority, as for `Dielectric`
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.medium.priority = priority;
+        self
+    }
+}
+
+impl Material for RoughDielectric {
+    fn scatter(
+        &self,
+        r: &Ray,
+        hit: &Record,
+        vec_dist: &Uniform<f64>,
+        rng: &mut ThreadRng,
+    ) -> Option<Scatter> {
+        scatter(
+            &self.medium,
+            &self.distribution,
+            self.tint,
+            r,
+            hit,
+            vec_dist,
+            rng,
+        )
+    }
+}
+
+/// Reflect or refract at the rough surface of `medium`, filtering refracted
+/// light by `tint`
+pub(super) fn scatter(
+    medium: &Medium,
+    distribution: &Ggx,
+    tint: Color,
+    r: &Ray,
+    hit: &Record,
+    vec_dist: &Uniform<f64>,
+    rng: &mut ThreadRng,
+) -> Option<Scatter> {
+    let interface = match Crossing::new(medium, r, hit, vec_dist, rng) {
+        Crossing::PassThrough(scatter) => return Some(scatter),
+        Crossing::Interface(interface) => interface,
+    };
+
+    let frame = hit.frame();
+    let wo = frame.to_local(-r.direction.unit());
+    if wo.z <= 0.0 {
+        return None;
+    }
+
+    let u1 = (vec_dist.sample(rng) + 1.0) / 2.0;
+    let u2 = (vec_dist.sample(rng) + 1.0) / 2.0;
+    let microfacet = distribution.sample_visible_normal(wo, u1, u2);
+
+    // Choose reflection or refraction in proportion to the Fresnel term,
+    // leaving only shadowing in the weight
+    let cos_theta = wo.dot(microfacet);
+    let reflectance = fresnel::dielectric(cos_theta, interface.eta_ratio);
+    let (wi, ray, color) = if reflectance > (vec_dist.sample(rng) + 1.0) / 2.0 {
+        let wi = (-wo).reflect(&microfacet);
+        if wi.z <= 0.0 {
+            return None;
+        }
+        (
+            wi,
+            interface.reflected(r, hit, frame.to_world(wi)),
+            Color::ONE,
+        )
+    } else {
+        let wi = (-wo).refract(&microfacet, interface.eta_ratio);
+        if wi.z >= 0.0 {
+            return None;
+        }
+        (wi, interface.refracted(r, hit, frame.to_world(wi)), tint)
+    };
+
+    Some(Scatter {
+        ray,
+        attenuation: distribution.shadowing(wo, wi) * color.schur(interface.weight),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::{Hittable, Sphere};
+    use crate::material::{Dielectric, FresnelModel};
+    use crate::vec::{Point, Vec3};
+    use std::sync::Arc;
+
+    /// Average red throughput of light shone through the middle of a glass
+    /// ball until it escapes
+    fn red_throughput(material: Arc<dyn Material>) -> f64 {
+        let ball = Sphere::new(Point::ZERO, 1.0, material);
+        let vec_dist = Uniform::new_inclusive(-1.0, 1.0);
+        let mut rng = rand::thread_rng();
+        let samples = 20000;
+        let total = (0..samples).fold(0.0, |total, _| {
+            let mut r = Ray::new(Point::new(-2.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+            let mut throughput = 1.0;
+            for _ in 0..50 {
+                let hit = match ball.hit(&r, 1.0e-6..=f64::INFINITY) {
+                    Some(hit) => hit,
+                    None => break,
+                };
+                throughput *= r.media.transmittance(hit.distance * r.direction.mag()).x;
+                match hit.material.scatter(&r, &hit, &vec_dist, &mut rng) {
+                    Some(scatter) => {
+                        throughput *= scatter.attenuation.x;
+                        r = scatter.ray;
+                    }
+                    None => {
+                        throughput = 0.0;
+                        break;
+                    }
+                }
+            }
+            total + throughput
+        });
+        total / samples as f64
+    }
+
+    #[test]
+    fn absorbs_and_tints_like_smooth_glass() {
+        let (tint, absorption) = (Color::new(0.5, 1.0, 1.0), Color::new(0.3, 0.0, 0.0));
+        let rough = red_throughput(Arc::new(RoughDielectric::colored(
+            RefractiveIndex::CrownGlass,
+            0.01,
+            tint,
+            absorption,
+        )));
+        let smooth = red_throughput(Arc::new(
+            Dielectric::colored(RefractiveIndex::CrownGlass, tint, absorption)
+                .with_fresnel(FresnelModel::Exact),
+        ));
+        assert!(rough < 0.5, "{}", rough);
+        assert!((rough - smooth).abs() < 0.02, "{} != {}", rough, smooth);
+    }
+}
@@ -1,13 +1,39 @@
+use crate::material::MediumStack;
+use crate::spectrum::SampledWavelengths;
 use crate::vec::{Point, Vec3};
 
 pub struct Ray {
     pub origin: Point,
     pub direction: Vec3,
+    /// Dielectric media containing the ray's origin
+    pub media: MediumStack,
+    /// Wavelengths carried by the path, for spectral rendering or once white
+    /// light has been split by dispersion
+    pub wavelengths: Option<SampledWavelengths>,
+    /// Moment the ray was sent, within the camera's shutter interval
+    pub time: f64,
 }
 
 impl Ray {
     pub fn new(origin: Point, direction: Vec3) -> Self {
-        Self { origin, direction }
+        Self {
+            origin,
+            direction,
+            media: MediumStack::default(),
+            wavelengths: None,
+            time: 0.0,
+        }
+    }
+
+    /// Continue the path from a scattering event, keeping its state
+    pub fn spawn(&self, origin: Point, direction: Vec3) -> Self {
+        Self {
+            origin,
+            direction,
+            media: self.media,
+            wavelengths: self.wavelengths,
+            time: self.time,
+        }
     }
 
     pub fn at(&self, t: f64) -> Point {
@@ -0,0 +1,264 @@
+use palette::{LinSrgb, Xyz};
+
+use crate::vec::Color;
+
+use std::ops::{Add, Mul};
+use std::sync::OnceLock;
+
+/// Shortest visible wavelength sampled, in nanometers
+pub const MIN_WAVELENGTH: f64 = 380.0;
+/// Longest visible wavelength sampled, in nanometers
+pub const MAX_WAVELENGTH: f64 = 780.0;
+
+/// Wavelength of the sodium D line, where refractive indices are usually quoted
+pub const SODIUM_D: f64 = 589.3;
+
+/// Map a uniform sample in 0.0..1.0 to a visible wavelength
+pub fn sample_wavelength(sample: f64) -> f64 {
+    MIN_WAVELENGTH + sample * (MAX_WAVELENGTH - MIN_WAVELENGTH)
+}
+
+/// CIE 1931 2° color matching functions, from the multi-lobe Gaussian fit of
+/// [Wyman, Sloan and Shirley](https://jcgt.org/published/0002/02/01/)
+pub fn cie_xyz(wavelength: f64) -> Color {
+    let g = |mu: f64, sigma_low: f64, sigma_high: f64| {
+        let sigma = if wavelength < mu {
+            sigma_low
+        } else {
+            sigma_high
+        };
+        (-0.5 * ((wavelength - mu) / sigma).powi(2)).exp()
+    };
+    Color::new(
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    )
+}
+
+/// Convert CIE XYZ to linear sRGB, which may be out of gamut
+pub fn xyz_to_rgb(xyz: Color) -> Color {
+    let rgb: LinSrgb<f64> = Xyz::new(xyz.x, xyz.y, xyz.z).into();
+    Color::new(rgb.red, rgb.green, rgb.blue)
+}
+
+/// Linear sRGB contribution of a single wavelength. Colors outside the sRGB
+/// gamut are clipped, and each channel is scaled so that the average over
+/// uniformly sampled wavelengths is white.
+pub fn wavelength_to_rgb(wavelength: f64) -> Color {
+    let clipped = |wavelength: f64| {
+        let rgb = xyz_to_rgb(cie_xyz(wavelength));
+        Color::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+    };
+
+    static WHITE: OnceLock<Color> = OnceLock::new();
+    let white = WHITE.get_or_init(|| {
+        const STEPS: usize = 1000;
+        (0..STEPS)
+            .map(|i| clipped(sample_wavelength((i as f64 + 0.5) / STEPS as f64)))
+            .fold(Color::ZERO, |sum, rgb| sum + rgb)
+            / STEPS as f64
+    });
+
+    let rgb = clipped(wavelength);
+    Color::new(rgb.x / white.x, rgb.y / white.y, rgb.z / white.z)
+}
+
+/// Number of wavelengths traced together along each path
+pub const SAMPLES: usize = 4;
+
+/// [Hero wavelength](https://doi.org/10.1111/cgf.12419) samples: one random
+/// wavelength plus others evenly spaced across the visible range
+#[derive(Debug, Clone, Copy)]
+pub struct SampledWavelengths {
+    lambda: [f64; SAMPLES],
+    secondary_terminated: bool,
+}
+
+impl SampledWavelengths {
+    /// Stratified wavelengths from a uniform sample in 0.0..1.0
+    pub fn sample_visible(sample: f64) -> Self {
+        let mut lambda = [0.0; SAMPLES];
+        for (i, l) in lambda.iter_mut().enumerate() {
+            *l = sample_wavelength((sample + i as f64 / SAMPLES as f64).fract());
+        }
+        Self {
+            lambda,
+            secondary_terminated: false,
+        }
+    }
+
+    /// A lone wavelength, as when white light is split by dispersion
+    pub fn single(wavelength: f64) -> Self {
+        Self {
+            lambda: [wavelength; SAMPLES],
+            secondary_terminated: true,
+        }
+    }
+
+    /// The wavelength that decides the path when wavelengths would diverge
+    pub fn hero(&self) -> f64 {
+        self.lambda[0]
+    }
+
+    pub fn is_secondary_terminated(&self) -> bool {
+        self.secondary_terminated
+    }
+
+    /// Drop all but the hero wavelength, e.g. after a dispersive refraction
+    pub fn terminate_secondary(&mut self) {
+        self.secondary_terminated = true;
+    }
+}
+
+/// Spectral quantity at each of a set of `SampledWavelengths`
+#[derive(Debug, Clone, Copy)]
+pub struct SampledSpectrum([f64; SAMPLES]);
+
+impl SampledSpectrum {
+    pub const ZERO: Self = Self([0.0; SAMPLES]);
+    pub const ONE: Self = Self([1.0; SAMPLES]);
+
+    /// Reflectance spectrum for an RGB color using the method of
+    /// [Smits](https://doi.org/10.1080/10867651.1999.10487511), which maps
+    /// white to a constant spectrum
+    pub fn from_rgb(rgb: Color, wavelengths: &SampledWavelengths) -> Self {
+        let mut values = [0.0; SAMPLES];
+        for (value, &lambda) in values.iter_mut().zip(&wavelengths.lambda) {
+            *value = smits::uplift(rgb, lambda);
+        }
+        Self(values)
+    }
+
+    /// Keep only the hero wavelength, reweighted to stand in for the others
+    pub fn terminate_secondary(self) -> Self {
+        let mut values = [0.0; SAMPLES];
+        values[0] = self.0[0] * SAMPLES as f64;
+        Self(values)
+    }
+
+    /// Monte Carlo estimate of CIE XYZ, normalized so that a constant spectrum
+    /// of 1.0 maps to the D65 white point
+    pub fn to_xyz(&self, wavelengths: &SampledWavelengths) -> Color {
+        let xyz = self
+            .0
+            .iter()
+            .zip(&wavelengths.lambda)
+            .map(|(&value, &lambda)| value * cie_xyz(lambda))
+            .fold(Color::ZERO, |sum, xyz| sum + xyz)
+            / SAMPLES as f64;
+
+        // Average of the matching functions over the sampled range
+        static WHITE: OnceLock<Color> = OnceLock::new();
+        let white = WHITE.get_or_init(|| {
+            const STEPS: usize = 1000;
+            (0..STEPS)
+                .map(|i| cie_xyz(sample_wavelength((i as f64 + 0.5) / STEPS as f64)))
+                .fold(Color::ZERO, |sum, xyz| sum + xyz)
+                / STEPS as f64
+        });
+        const D65: Color = Color {
+            x: 0.95047,
+            y: 1.0,
+            z: 1.08883,
+        };
+        Color::new(
+            xyz.x / white.x * D65.x,
+            xyz.y / white.y * D65.y,
+            xyz.z / white.z * D65.z,
+        )
+    }
+
+    /// Linear sRGB, clamped to be non-negative
+    pub fn to_rgb(&self, wavelengths: &SampledWavelengths) -> Color {
+        let rgb = xyz_to_rgb(self.to_xyz(wavelengths));
+        Color::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+    }
+}
+
+impl Add for SampledSpectrum {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        let mut values = self.0;
+        for (value, other) in values.iter_mut().zip(&other.0) {
+            *value += other;
+        }
+        Self(values)
+    }
+}
+
+impl Mul for SampledSpectrum {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        let mut values = self.0;
+        for (value, other) in values.iter_mut().zip(&other.0) {
+            *value *= other;
+        }
+        Self(values)
+    }
+}
+
+mod smits {
+    use crate::vec::Color;
+
+    /// Basis spectra sampled in ten even bins from 380 nm to 720 nm
+    const BINS: usize = 10;
+    const START: f64 = 380.0;
+    const END: f64 = 720.0;
+
+    const WHITE: [f64; BINS] = [
+        1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+    ];
+    const CYAN: [f64; BINS] = [
+        0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+    ];
+    const MAGENTA: [f64; BINS] = [
+        1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+    ];
+    const YELLOW: [f64; BINS] = [
+        0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+    ];
+    const RED: [f64; BINS] = [
+        0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+    ];
+    const GREEN: [f64; BINS] = [
+        0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+    ];
+    const BLUE: [f64; BINS] = [
+        1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+    ];
+
+    /// Value of the spectrum for `rgb` at one wavelength in nanometers
+    pub fn uplift(rgb: Color, wavelength: f64) -> f64 {
+        let bin =
+            (((wavelength - START) / (END - START) * BINS as f64).max(0.0) as usize).min(BINS - 1);
+        let (r, g, b) = rgb.components();
+
+        // White for the smallest component, then the complementary color of
+        // the next smallest, then the primary of the largest
+        if r <= g && r <= b {
+            let base = r * WHITE[bin];
+            if g <= b {
+                base + (g - r) * CYAN[bin] + (b - g) * BLUE[bin]
+            } else {
+                base + (b - r) * CYAN[bin] + (g - b) * GREEN[bin]
+            }
+        } else if g <= r && g <= b {
+            let base = g * WHITE[bin];
+            if r <= b {
+                base + (r - g) * MAGENTA[bin] + (b - r) * BLUE[bin]
+            } else {
+                base + (b - g) * MAGENTA[bin] + (r - b) * RED[bin]
+            }
+        } else {
+            let base = b * WHITE[bin];
+            if r <= g {
+                base + (r - b) * YELLOW[bin] + (g - r) * GREEN[bin]
+            } else {
+                base + (g - b) * YELLOW[bin] + (r - g) * RED[bin]
+            }
+        }
+    }
+}
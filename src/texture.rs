@@ -0,0 +1,17 @@
+use crate::vec::{Color, Point};
+
+pub mod image;
+pub mod noise;
+pub mod perlin;
+pub mod solid;
+
+pub use image::{ImageTexture, WrapMode};
+pub use noise::{Marble, Noise, Wood};
+pub use perlin::Perlin;
+pub use solid::SolidColor;
+
+/// Color that varies over a surface
+pub trait Texture: Send + Sync {
+    /// Color at surface coordinates `(u, v)` and world-space `point`
+    fn value(&self, u: f64, v: f64, point: &Point) -> Color;
+}
@@ -0,0 +1,210 @@
+use palette::Srgb;
+
+use super::Texture;
+use crate::vec::{Color, Point};
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// How texture coordinates outside of 0.0..=1.0 map onto the image
+#[derive(Debug, Clone, Copy)]
+pub enum WrapMode {
+    /// Tile the image
+    Repeat,
+    /// Extend the edge pixels
+    Clamp,
+    /// Tile the image, flipping every other copy
+    Mirror,
+}
+
+impl WrapMode {
+    /// Map a possibly out-of-bounds pixel index into `0..size`
+    fn wrap(self, i: i64, size: usize) -> usize {
+        let size = size as i64;
+        let i = match self {
+            WrapMode::Repeat => i.rem_euclid(size),
+            WrapMode::Clamp => i.clamp(0, size - 1),
+            WrapMode::Mirror => {
+                let i = i.rem_euclid(2 * size);
+                if i < size {
+                    i
+                } else {
+                    2 * size - 1 - i
+                }
+            }
+        };
+        i as usize
+    }
+}
+
+/// Texture backed by a PNG image, stored as linear RGB
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    /// Row-major linear colors, top row first
+    pixels: Vec<Color>,
+    wrap: WrapMode,
+}
+
+impl ImageTexture {
+    /// Decode a PNG file of any bit depth and color type. Pixel values are
+    /// assumed to be sRGB encoded, and any alpha channel is ignored.
+    pub fn open<P: AsRef<Path>>(path: P, wrap: WrapMode) -> Result<Self, png::DecodingError> {
+        Self::decode(File::open(path)?, wrap, true)
+    }
+
+    /// Decode a PNG file holding non-color data, such as a normal or bump map,
+    /// keeping the stored values as-is
+    pub fn open_linear<P: AsRef<Path>>(
+        path: P,
+        wrap: WrapMode,
+    ) -> Result<Self, png::DecodingError> {
+        Self::decode(File::open(path)?, wrap, false)
+    }
+
+    fn decode<R: Read>(data: R, wrap: WrapMode, srgb: bool) -> Result<Self, png::DecodingError> {
+        let mut decoder = png::Decoder::new(data);
+        // Palette and low bit depth images are expanded to 8-bit samples
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let (info, mut reader) = decoder.read_info()?;
+        let mut buffer = vec![0; info.buffer_size()];
+        reader.next_frame(&mut buffer)?;
+
+        let samples: Vec<f64> = match info.bit_depth {
+            png::BitDepth::Sixteen => buffer
+                .chunks_exact(2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]) as f64 / u16::MAX as f64)
+                .collect(),
+            _ => buffer.iter().map(|&b| b as f64 / u8::MAX as f64).collect(),
+        };
+
+        let to_linear = |r: f64, g: f64, b: f64| {
+            if srgb {
+                let linear = Srgb::new(r, g, b).into_linear();
+                Color::new(linear.red, linear.green, linear.blue)
+            } else {
+                Color::new(r, g, b)
+            }
+        };
+        let pixels = match info.color_type {
+            png::ColorType::Grayscale => samples.iter().map(|&l| to_linear(l, l, l)).collect(),
+            png::ColorType::GrayscaleAlpha => samples
+                .chunks_exact(2)
+                .map(|p| to_linear(p[0], p[0], p[0]))
+                .collect(),
+            png::ColorType::RGB => samples
+                .chunks_exact(3)
+                .map(|p| to_linear(p[0], p[1], p[2]))
+                .collect(),
+            png::ColorType::RGBA => samples
+                .chunks_exact(4)
+                .map(|p| to_linear(p[0], p[1], p[2]))
+                .collect(),
+            png::ColorType::Indexed => {
+                return Err(png::DecodingError::Format(
+                    "Palette was not expanded".into(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            width: info.width as usize,
+            height: info.height as usize,
+            pixels,
+            wrap,
+        })
+    }
+
+    fn pixel(&self, x: i64, y: i64) -> Color {
+        let x = self.wrap.wrap(x, self.width);
+        let y = self.wrap.wrap(y, self.height);
+        self.pixels[y * self.width + x]
+    }
+}
+
+impl Texture for ImageTexture {
+    /// Bilinear interpolation between the four nearest pixel centers
+    fn value(&self, u: f64, v: f64, _point: &Point) -> Color {
+        // Image rows run top to bottom, while v runs bottom to top
+        let x = u * self.width as f64 - 0.5;
+        let y = (1.0 - v) * self.height as f64 - 0.5;
+
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = (1.0 - tx) * self.pixel(x0, y0) + tx * self.pixel(x0 + 1, y0);
+        let bottom = (1.0 - tx) * self.pixel(x0, y0 + 1) + tx * self.pixel(x0 + 1, y0 + 1);
+        (1.0 - ty) * top + ty * bottom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(width: u32, height: u32, color_type: png::ColorType, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, width, height);
+            encoder.set_color(color_type);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(data).unwrap();
+        }
+        bytes
+    }
+
+    fn assert_color(actual: Color, expected: (f64, f64, f64)) {
+        let (r, g, b) = actual.components();
+        assert!(
+            (r - expected.0).abs() < 1e-4
+                && (g - expected.1).abs() < 1e-4
+                && (b - expected.2).abs() < 1e-4,
+            "{:?} != {:?}",
+            actual.components(),
+            expected
+        );
+    }
+
+    #[test]
+    fn decodes_srgb_pixels_top_row_first() {
+        // Red and mid gray on top, blue and white below
+        #[rustfmt::skip]
+        let png = encode(2, 2, png::ColorType::RGB, &[
+            255, 0, 0,    128, 128, 128,
+            0, 0, 255,    255, 255, 255,
+        ]);
+        let texture = ImageTexture::decode(&png[..], WrapMode::Clamp, true).unwrap();
+
+        let gray = 0.215861;
+        let p = Point::ZERO;
+        assert_color(texture.value(0.25, 0.75, &p), (1.0, 0.0, 0.0));
+        assert_color(texture.value(0.75, 0.75, &p), (gray, gray, gray));
+        assert_color(texture.value(0.25, 0.25, &p), (0.0, 0.0, 1.0));
+        assert_color(texture.value(0.75, 0.25, &p), (1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn wrap_modes_pick_texels_outside_the_image() {
+        let png = encode(3, 1, png::ColorType::Grayscale, &[0, 51, 255]);
+        let levels = [0.0, 0.2, 1.0];
+        // Pixel columns -2, -1, 3, 4 and 5, and the column each maps to
+        let columns = [-2, -1, 3, 4, 5];
+        let expected = [
+            (WrapMode::Repeat, [1, 2, 0, 1, 2]),
+            (WrapMode::Clamp, [0, 0, 2, 2, 2]),
+            (WrapMode::Mirror, [1, 0, 2, 1, 0]),
+        ];
+
+        for &(wrap, texels) in &expected {
+            let texture = ImageTexture::decode(&png[..], wrap, false).unwrap();
+            for (&column, &texel) in columns.iter().zip(&texels) {
+                let u = (column as f64 + 0.5) / 3.0;
+                let level = levels[texel];
+                assert_color(texture.value(u, 0.5, &Point::ZERO), (level, level, level));
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use super::{Perlin, Texture};
+use crate::vec::{Color, Point};
+
+/// Grayscale fractal noise
+pub struct Noise {
+    perlin: Perlin,
+    scale: f64,
+    octaves: usize,
+}
+
+impl Noise {
+    pub fn new(seed: u64, scale: f64, octaves: usize) -> Self {
+        Self {
+            perlin: Perlin::new(seed),
+            scale,
+            octaves,
+        }
+    }
+}
+
+impl Texture for Noise {
+    fn value(&self, _u: f64, _v: f64, point: &Point) -> Color {
+        let n = self
+            .perlin
+            .fbm(&(self.scale * *point), self.octaves, 2.0, 0.5);
+        (0.5 * (1.0 + n)).clamp(0.0, 1.0) * Color::ONE
+    }
+}
+
+/// Veined stone: sine bands along Z distorted by turbulence
+pub struct Marble {
+    perlin: Perlin,
+    scale: f64,
+    base: Color,
+    vein: Color,
+}
+
+impl Marble {
+    pub fn new(seed: u64, scale: f64, base: Color, vein: Color) -> Self {
+        Self {
+            perlin: Perlin::new(seed),
+            scale,
+            base,
+            vein,
+        }
+    }
+}
+
+impl Texture for Marble {
+    fn value(&self, _u: f64, _v: f64, point: &Point) -> Color {
+        let p = self.scale * *point;
+        let t = 0.5 * (1.0 + (p.z + 10.0 * self.perlin.turbulence(&p, 7)).sin());
+        t * self.base + (1.0 - t) * self.vein
+    }
+}
+
+/// Growth rings around the Y axis, wobbled by fractal noise
+pub struct Wood {
+    perlin: Perlin,
+    /// Rings per unit distance from the axis
+    ring_frequency: f64,
+    light: Color,
+    dark: Color,
+}
+
+impl Wood {
+    pub fn new(seed: u64, ring_frequency: f64, light: Color, dark: Color) -> Self {
+        Self {
+            perlin: Perlin::new(seed),
+            ring_frequency,
+            light,
+            dark,
+        }
+    }
+}
+
+impl Texture for Wood {
+    fn value(&self, _u: f64, _v: f64, point: &Point) -> Color {
+        let distortion = 0.2 * self.perlin.fbm(&(4.0 * *point), 4, 2.0, 0.5);
+        let radius = (point.x.powi(2) + point.z.powi(2)).sqrt() + distortion;
+        let ring = (radius * self.ring_frequency).fract();
+        // Sharpen the transition from early wood to late wood
+        let t = ring.powi(3);
+        (1.0 - t) * self.light + t * self.dark
+    }
+}
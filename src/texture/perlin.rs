@@ -0,0 +1,156 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::vec::Point;
+
+const TABLE_SIZE: usize = 256;
+
+/// [Improved Perlin noise](https://mrl.cs.nyu.edu/~perlin/noise/) over 3D space.
+///
+/// The permutation table is shuffled from a seed, so the same seed always
+/// produces the same noise field.
+pub struct Perlin {
+    permutation: [u8; 2 * TABLE_SIZE],
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut table: Vec<u8> = (0..TABLE_SIZE).map(|i| i as u8).collect();
+        table.shuffle(&mut rng);
+
+        let mut permutation = [0; 2 * TABLE_SIZE];
+        for (i, p) in permutation.iter_mut().enumerate() {
+            *p = table[i % TABLE_SIZE];
+        }
+        Self { permutation }
+    }
+
+    /// Smooth noise in roughly -1.0..=1.0
+    pub fn noise(&self, p: &Point) -> f64 {
+        let (xi, x) = split(p.x);
+        let (yi, y) = split(p.y);
+        let (zi, z) = split(p.z);
+
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let perm = |i: usize| self.permutation[i] as usize;
+        let a = perm(xi) + yi;
+        let aa = perm(a) + zi;
+        let ab = perm(a + 1) + zi;
+        let b = perm(xi + 1) + yi;
+        let ba = perm(b) + zi;
+        let bb = perm(b + 1) + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, grad(perm(aa), x, y, z), grad(perm(ba), x - 1.0, y, z)),
+                lerp(
+                    u,
+                    grad(perm(ab), x, y - 1.0, z),
+                    grad(perm(bb), x - 1.0, y - 1.0, z),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad(perm(aa + 1), x, y, z - 1.0),
+                    grad(perm(ba + 1), x - 1.0, y, z - 1.0),
+                ),
+                lerp(
+                    u,
+                    grad(perm(ab + 1), x, y - 1.0, z - 1.0),
+                    grad(perm(bb + 1), x - 1.0, y - 1.0, z - 1.0),
+                ),
+            ),
+        )
+    }
+
+    /// Fractional Brownian motion: sum of `octaves` layers of noise, each at
+    /// `lacunarity` times the frequency and `gain` times the amplitude of the
+    /// previous layer.
+    pub fn fbm(&self, p: &Point, octaves: usize, lacunarity: f64, gain: f64) -> f64 {
+        let mut sum = 0.0;
+        let mut point = *p;
+        let mut amplitude = 1.0;
+        for _ in 0..octaves {
+            sum += amplitude * self.noise(&point);
+            point *= lacunarity;
+            amplitude *= gain;
+        }
+        sum
+    }
+
+    /// Sum of absolute noise values over `octaves` layers, doubling frequency
+    /// and halving amplitude each layer
+    pub fn turbulence(&self, p: &Point, octaves: usize) -> f64 {
+        let mut sum = 0.0;
+        let mut point = *p;
+        let mut amplitude = 1.0;
+        for _ in 0..octaves {
+            sum += amplitude * self.noise(&point).abs();
+            point *= 2.0;
+            amplitude *= 0.5;
+        }
+        sum
+    }
+}
+
+/// Split a coordinate into its lattice cell index and the offset within the cell
+fn split(x: f64) -> (usize, f64) {
+    let floor = x.floor();
+    (
+        (floor as i64).rem_euclid(TABLE_SIZE as i64) as usize,
+        x - floor,
+    )
+}
+
+/// Quintic smoothstep 6t^5 - 15t^4 + 10t^3
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// Dot product of the offset with one of twelve cube-edge gradient directions
+fn grad(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = match h {
+        0..=3 => y,
+        12 | 14 => x,
+        _ => z,
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Noise at points off the integer lattice, where it is always zero
+    fn samples(perlin: &Perlin) -> Vec<f64> {
+        (0..100)
+            .map(|i| {
+                let i = i as f64;
+                perlin.noise(&Point::new(0.37 * i, 0.61 * i + 0.2, 1.13 * i + 0.5))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_noise() {
+        assert_eq!(samples(&Perlin::new(7)), samples(&Perlin::new(7)));
+    }
+
+    #[test]
+    fn different_seeds_give_different_noise() {
+        assert_ne!(samples(&Perlin::new(7)), samples(&Perlin::new(8)));
+    }
+}
@@ -50,6 +50,15 @@ impl Vec3 {
         }
     }
 
+    /// Entrywise exponential
+    pub fn exp(&self) -> Self {
+        Self {
+            x: self.x.exp(),
+            y: self.y.exp(),
+            z: self.z.exp(),
+        }
+    }
+
     // Length of the vector
     pub fn mag(&self) -> f64 {
         self.mag_squared().sqrt()
@@ -142,6 +151,342 @@ impl Vec3 {
     }
 }
 
+/// Orthonormal basis with the normal `n` as the local Z axis
+#[derive(Debug, Clone, Copy)]
+pub struct Frame {
+    pub s: Vec3,
+    pub t: Vec3,
+    pub n: Vec3,
+}
+
+impl Frame {
+    /// Frame around the unit normal `n`, with `s` pointing along the part of
+    /// `tangent` perpendicular to `n`
+    pub fn new(n: Vec3, tangent: Vec3) -> Self {
+        let mut s = tangent - tangent.dot(n) * n;
+        if s.near_zero() {
+            // Any perpendicular will do
+            s = if n.x.abs() > 0.9 {
+                Vec3::new(0.0, 1.0, 0.0)
+            } else {
+                Vec3::new(1.0, 0.0, 0.0)
+            };
+            s -= s.dot(n) * n;
+        }
+        let s = s.unit();
+        Self {
+            s,
+            t: n.cross(s),
+            n,
+        }
+    }
+
+    /// Express a world-space vector in this frame
+    pub fn to_local(&self, v: Vec3) -> Vec3 {
+        Vec3::new(v.dot(self.s), v.dot(self.t), v.dot(self.n))
+    }
+
+    /// Express a vector in this frame in world space
+    pub fn to_world(&self, v: Vec3) -> Vec3 {
+        v.x * self.s + v.y * self.t + v.z * self.n
+    }
+}
+
+/// Affine or projective transform as a row-major 4x4 matrix acting on column
+/// vectors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4(pub [[f64; 4]; 4]);
+
+impl Mat4 {
+    pub const IDENTITY: Self = Self([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+
+    pub fn translate(offset: Vec3) -> Self {
+        Self([
+            [1.0, 0.0, 0.0, offset.x],
+            [0.0, 1.0, 0.0, offset.y],
+            [0.0, 0.0, 1.0, offset.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn scale(factors: Vec3) -> Self {
+        Self([
+            [factors.x, 0.0, 0.0, 0.0],
+            [0.0, factors.y, 0.0, 0.0],
+            [0.0, 0.0, factors.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Counterclockwise rotation by an angle in degrees about an axis through
+    /// the origin
+    pub fn rotate(axis: Vec3, degrees: f64) -> Self {
+        let (x, y, z) = axis.unit().components();
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let c = 1.0 - cos;
+        Self([
+            [
+                cos + x * x * c,
+                x * y * c - z * sin,
+                x * z * c + y * sin,
+                0.0,
+            ],
+            [
+                y * x * c + z * sin,
+                cos + y * y * c,
+                y * z * c - x * sin,
+                0.0,
+            ],
+            [
+                z * x * c - y * sin,
+                z * y * c + x * sin,
+                cos + z * z * c,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// View transform from world space to a right-handed camera space where
+    /// the camera sits at the origin looking down -Z with +Y up
+    pub fn look_at(eye: Point, target: Point, up: Vec3) -> Self {
+        let w = (eye - target).unit();
+        let u = up.cross(w).unit();
+        let v = w.cross(u);
+        Self([
+            [u.x, u.y, u.z, -u.dot(eye)],
+            [v.x, v.y, v.z, -v.dot(eye)],
+            [w.x, w.y, w.z, -w.dot(eye)],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Projection from camera space (looking down -Z) to clip space, mapping
+    /// depths between the near and far planes to -1.0..=1.0 as in OpenGL
+    pub fn perspective(vfov: f64, aspect_ratio: f64, near: f64, far: f64) -> Self {
+        let f = 1.0 / (vfov.to_radians() / 2.0).tan();
+        Self([
+            [f / aspect_ratio, 0.0, 0.0, 0.0],
+            [0.0, f, 0.0, 0.0],
+            [
+                0.0,
+                0.0,
+                (far + near) / (near - far),
+                2.0 * far * near / (near - far),
+            ],
+            [0.0, 0.0, -1.0, 0.0],
+        ])
+    }
+
+    /// Compose with a transform applied after this one, so that
+    /// `a.then(b).then(c)` applies `a`, then `b`, then `c`
+    pub fn then(self, next: Self) -> Self {
+        next * self
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.0[j][i];
+            }
+        }
+        Self(m)
+    }
+
+    /// Inverse by Gauss-Jordan elimination, or `None` if the matrix is
+    /// singular
+    pub fn inverse(&self) -> Option<Self> {
+        let mut m = self.0;
+        let mut inverse = Self::IDENTITY.0;
+        for column in 0..4 {
+            let pivot = (column..4)
+                .max_by(|&a, &b| m[a][column].abs().total_cmp(&m[b][column].abs()))
+                .unwrap_or(column);
+            if m[pivot][column].abs() < 1.0e-12 {
+                return None;
+            }
+            m.swap(column, pivot);
+            inverse.swap(column, pivot);
+
+            let scale = 1.0 / m[column][column];
+            for j in 0..4 {
+                m[column][j] *= scale;
+                inverse[column][j] *= scale;
+            }
+            for row in 0..4 {
+                if row != column {
+                    let factor = m[row][column];
+                    for j in 0..4 {
+                        m[row][j] -= factor * m[column][j];
+                        inverse[row][j] -= factor * inverse[column][j];
+                    }
+                }
+            }
+        }
+        Some(Self(inverse))
+    }
+
+    /// Transform a position, including translation and any projective divide
+    pub fn transform_point(&self, p: Point) -> Point {
+        let m = &self.0;
+        let w = m[3][0] * p.x + m[3][1] * p.y + m[3][2] * p.z + m[3][3];
+        (self.transform_vector(p) + Vec3::new(m[0][3], m[1][3], m[2][3])) / w
+    }
+
+    /// Transform a direction, ignoring translation
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let m = &self.0;
+        Vec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Self;
+
+    /// Compose transforms, applying `other` first
+    fn mul(self, other: Self) -> Self::Output {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.0[i][k] * other.0[k][j]).sum();
+            }
+        }
+        Self(m)
+    }
+}
+
+/// Unit quaternion representing a rotation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Quat {
+    pub const IDENTITY: Self = Self {
+        w: 1.0,
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Self { w, x, y, z }
+    }
+
+    /// Counterclockwise rotation by an angle in degrees about an axis
+    pub fn from_axis_angle(axis: Vec3, degrees: f64) -> Self {
+        let (sin, cos) = (degrees.to_radians() / 2.0).sin_cos();
+        let axis = sin * axis.unit();
+        Self::new(cos, axis.x, axis.y, axis.z)
+    }
+
+    pub fn dot(&self, other: Self) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    pub fn unit(&self) -> Self {
+        let mag = self.dot(*self).sqrt();
+        Self::new(self.w / mag, self.x / mag, self.y / mag, self.z / mag)
+    }
+
+    /// Rotate a vector. Self should be a unit quaternion.
+    pub fn rotate(&self, v: Vec3) -> Vec3 {
+        let axis = Vec3::new(self.x, self.y, self.z);
+        let t = 2.0 * axis.cross(v);
+        v + self.w * t + axis.cross(t)
+    }
+
+    /// Spherical linear interpolation along the shortest arc, from `self` at
+    /// `t = 0.0` to `other` at `t = 1.0`
+    pub fn slerp(&self, other: Self, t: f64) -> Self {
+        let mut cos_theta = self.dot(other);
+        // q and -q are the same rotation; take the shorter way around
+        let other = if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            Self::new(-other.w, -other.x, -other.y, -other.z)
+        } else {
+            other
+        };
+
+        let (a, b) = if cos_theta > 0.9995 {
+            // Nearly parallel, where linear interpolation is accurate and
+            // avoids dividing by a vanishing sine
+            (1.0 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (
+                ((1.0 - t) * theta).sin() / sin_theta,
+                (t * theta).sin() / sin_theta,
+            )
+        };
+        Self::new(
+            a * self.w + b * other.w,
+            a * self.x + b * other.x,
+            a * self.y + b * other.y,
+            a * self.z + b * other.z,
+        )
+        .unit()
+    }
+
+    /// Equivalent rotation matrix. Self should be a unit quaternion.
+    pub fn to_mat4(&self) -> Mat4 {
+        let Self { w, x, y, z } = *self;
+        Mat4([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+impl Mul for Quat {
+    type Output = Self;
+
+    /// Hamilton product, which rotates by `other` and then by `self`
+    fn mul(self, other: Self) -> Self::Output {
+        Self {
+            w: self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+            x: self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            y: self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            z: self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+        }
+    }
+}
+
 impl Add for Vec3 {
     type Output = Self;
 
@@ -259,3 +604,127 @@ impl std::fmt::Display for Vec3 {
         write!(f, "[{}, {}, {}]", self.x, self.y, self.z)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPS: f64 = 1.0e-9;
+
+    fn assert_vec_eq(a: Vec3, b: Vec3) {
+        assert!((a - b).mag() < EPS, "{} != {}", a, b);
+    }
+
+    fn assert_mat_eq(a: Mat4, b: Mat4) {
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((a.0[i][j] - b.0[i][j]).abs() < EPS, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_undoes_transform() {
+        let m = Mat4::translate(Vec3::new(1.0, -2.0, 3.0))
+            * Mat4::rotate(Vec3::new(1.0, 1.0, 0.0), 40.0)
+            * Mat4::scale(Vec3::new(2.0, 0.5, 3.0));
+        let inverse = m.inverse().unwrap();
+        assert_mat_eq(m * inverse, Mat4::IDENTITY);
+        assert_mat_eq(inverse * m, Mat4::IDENTITY);
+
+        let p = Point::new(0.3, 4.0, -1.0);
+        assert_vec_eq(inverse.transform_point(m.transform_point(p)), p);
+    }
+
+    #[test]
+    fn singular_matrix_has_no_inverse() {
+        assert!(Mat4::scale(Vec3::new(1.0, 0.0, 1.0)).inverse().is_none());
+    }
+
+    #[test]
+    fn transpose_swaps_rows_and_columns() {
+        let m = Mat4::translate(Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(m.transpose().0[3], [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(m.transpose().transpose(), m);
+    }
+
+    #[test]
+    fn then_applies_in_order() {
+        let m = Mat4::scale(Vec3::new(2.0, 2.0, 2.0)).then(Mat4::translate(Vec3::ONE));
+        assert_vec_eq(m.transform_point(Point::ONE), Point::new(3.0, 3.0, 3.0));
+        assert_vec_eq(m.transform_vector(Vec3::ONE), Vec3::new(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn rotation_is_counterclockwise() {
+        let m = Mat4::rotate(Vec3::new(0.0, 0.0, 1.0), 90.0);
+        assert_vec_eq(
+            m.transform_vector(Vec3::new(1.0, 0.0, 0.0)),
+            Vec3::new(0.0, 1.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn look_at_moves_target_onto_negative_z() {
+        let eye = Point::new(13.0, 2.0, 3.0);
+        let view = Mat4::look_at(eye, Point::ZERO, Vec3::new(0.0, 1.0, 0.0));
+        assert_vec_eq(view.transform_point(eye), Point::ZERO);
+        assert_vec_eq(
+            view.transform_point(Point::ZERO),
+            Point::new(0.0, 0.0, -eye.mag()),
+        );
+    }
+
+    #[test]
+    fn perspective_maps_near_and_far_planes() {
+        let projection = Mat4::perspective(90.0, 2.0, 1.0, 100.0);
+        assert_vec_eq(
+            projection.transform_point(Point::new(0.0, 1.0, -1.0)),
+            Point::new(0.0, 1.0, -1.0),
+        );
+        assert_vec_eq(
+            projection.transform_point(Point::new(200.0, 0.0, -100.0)),
+            Point::new(1.0, 0.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn quaternion_matches_matrix_rotation() {
+        let axis = Vec3::new(1.0, 2.0, -0.5);
+        let q = Quat::from_axis_angle(axis, 70.0);
+        let m = Mat4::rotate(axis, 70.0);
+        let v = Vec3::new(0.2, -1.0, 3.0);
+        assert_vec_eq(q.rotate(v), m.transform_vector(v));
+        assert_mat_eq(q.to_mat4(), m);
+    }
+
+    #[test]
+    fn quaternion_product_composes_rotations() {
+        let a = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 30.0);
+        let b = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), 45.0);
+        let v = Vec3::new(1.0, 2.0, 3.0);
+        assert_vec_eq((a * b).rotate(v), a.rotate(b.rotate(v)));
+        assert_vec_eq((a * a.conjugate()).rotate(v), v);
+    }
+
+    #[test]
+    fn slerp_interpolates_angle_linearly() {
+        let axis = Vec3::new(0.0, 0.0, 1.0);
+        let a = Quat::from_axis_angle(axis, 10.0);
+        let b = Quat::from_axis_angle(axis, 110.0);
+        let halfway = a.slerp(b, 0.5);
+        let expected = Quat::from_axis_angle(axis, 60.0);
+        assert!((halfway.dot(expected) - 1.0).abs() < EPS);
+        assert!((a.slerp(b, 0.0).dot(a) - 1.0).abs() < EPS);
+        assert!((a.slerp(b, 1.0).dot(b) - 1.0).abs() < EPS);
+    }
+
+    #[test]
+    fn slerp_takes_shortest_arc() {
+        let a = Quat::IDENTITY;
+        let b = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 90.0);
+        let negated = Quat::new(-b.w, -b.x, -b.y, -b.z);
+        let v = Vec3::new(1.0, 0.0, 0.0);
+        assert_vec_eq(a.slerp(negated, 0.5).rotate(v), a.slerp(b, 0.5).rotate(v));
+    }
+}